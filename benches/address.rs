@@ -13,7 +13,13 @@ use ringct::{
         Scalar,
         Random
     },
-    address::ECDHPrivateKey,
+    address::{
+        ECDHPrivateKey,
+        Recipient,
+        cryptonote::CryptoNotePrivate,
+        scanner::Scanner
+    },
+    Commitment
 };
 
 fn ecdh_benchmark(c: &mut Criterion) {
@@ -44,5 +50,31 @@ fn ecdh_benchmark(c: &mut Criterion) {
     }));
 }
 
-criterion_group!(rangeproofs, ecdh_benchmark);
+fn scanner_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Scanner");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    let keys = CryptoNotePrivate::generate().to_view_only();
+    let address = CryptoNotePrivate::generate().to_public();
+    let outputs: Vec<(Recipient, Commitment)> = (0..10000).map(|_| {
+        let (blinding, recipient) = address.send(100);
+        (recipient, Commitment::commit(100, blinding))
+    }).collect();
+
+    group.bench_with_input(BenchmarkId::new("Scan", "Individual"), &outputs,
+    |b, outputs| b.iter(|| {
+        for (recipient, commitment) in outputs {
+            black_box(keys.receive(recipient, commitment));
+        }
+    }));
+
+    let scanner = Scanner::new();
+    group.bench_with_input(BenchmarkId::new("Scan", "Batch"), &outputs,
+    |b, outputs| b.iter(|| {
+        black_box(scanner.scan_cryptonote(&keys, outputs));
+    }));
+}
+
+criterion_group!(rangeproofs, ecdh_benchmark, scanner_benchmark);
 criterion_main!(rangeproofs);
\ No newline at end of file
@@ -52,6 +52,15 @@ where P: FromUniformBytes + Compressable + Clone
         })
     }
 
+    /// Return a copy of these parameters which uses different base points for Pedersen commitments,
+    /// reusing the (expensive to generate) bulletproof generators
+    pub fn with_pc_gens(&self, pc_gens: PedersenGens<P>) -> Self {
+        Self {
+            bp_gens: self.bp_gens.clone(),
+            pc_gens,
+        }
+    }
+
     /// Return a reference to the non-public bulletproof generators
     pub fn bp_gens(&self) -> &BulletproofGens<P> {
         &self.bp_gens
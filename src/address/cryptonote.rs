@@ -6,12 +6,15 @@
 
 //! Cryptonote-style stealth addresses
 
+use rand::{thread_rng, Rng};
 use zeroize::Zeroize;
 
 use crate::internal_common::*;
 use super::{
     ecdh::*,
-    Recipient
+    Recipient,
+    PaymentId,
+    outgoing::OutgoingViewKey
 };
 
 ///Private keys of CryptoNote address.
@@ -39,6 +42,11 @@ pub struct CryptoNotePrivate {
         }
     }
 
+    ///Derive the outgoing view key of these keys, for recovering sent payments.
+    pub fn outgoing_view_key(&self) -> OutgoingViewKey {
+        return OutgoingViewKey::from_spend(&self.spend)
+    }
+
     ///Given a public key, calculate the "shared secret" of these keys.
     ///
     ///**The public key should not be reused.**
@@ -48,8 +56,16 @@ pub struct CryptoNotePrivate {
 
     ///Deterministically derive a unique ephemeral private key given a shared secret.
     ///**These keys should never be reused.**
+    ///
+    ///This uses the legacy derivation, see `derive_key_with_version` for new enotes.
     pub fn derive_key(&self, shared_secret: SharedSecret) -> Scalar {
-        return self.spend + shared_secret.as_scalar()
+        return self.derive_key_with_version(shared_secret, DerivationVersion::Legacy)
+    }
+
+    ///Deterministically derive a unique ephemeral private key given a shared secret and derivation version.
+    ///**These keys should never be reused.**
+    pub fn derive_key_with_version(&self, shared_secret: SharedSecret, version: DerivationVersion) -> Scalar {
+        return self.spend + shared_secret.key_offset(version)
     }
 
     ///Generate a random new private key.
@@ -70,51 +86,65 @@ pub struct CryptoNotePrivate {
 
     ///"Receive" a payment, decrypting its content, given the pedersen commitment.
     ///
-    ///Returns `Some(EnoteKeys)` if the enote belongs to these keys, or `None` if not.
-    pub fn receive(&self, recipient: &Recipient, commitment: &Commitment) -> Option<EnoteKeys> {
+    ///Returns `Some((EnoteKeys, payment ID))` if the enote belongs to these keys, or `None` if not.
+    ///The payment ID is only included if the payment was sent to an integrated address.
+    pub fn receive(&self, recipient: &Recipient, commitment: &Commitment) -> Option<(EnoteKeys, Option<PaymentId>)> {
         if let Some(transaction_key) = recipient.transaction_key {
-            return self.receive_internal(recipient, commitment, transaction_key)
+            return self.receive_internal(recipient, commitment, transaction_key, None)
         };
         return None
     }
 
     ///"Receive" a payment, decrypting its content, given the pedersen commitment and a transaction/ECDH key.
     ///
-    ///Returns `Some(EnoteKeys)` if the enote belongs to these keys, or `None` if not.
+    ///Returns `Some((EnoteKeys, payment ID))` if the enote belongs to these keys, or `None` if not.
+    ///The payment ID is only included if the payment was sent to an integrated address.
     pub fn receive_with_key(
         &self, recipient: &Recipient, commitment: &Commitment, transaction_key: RistrettoPoint
-    ) -> Option<EnoteKeys> {
-        return self.receive_internal(recipient, commitment, transaction_key)
+    ) -> Option<(EnoteKeys, Option<PaymentId>)> {
+        return self.receive_internal(recipient, commitment, transaction_key, None)
+    }
+
+    ///"Receive" a payment, decrypting its content, given the pedersen commitment,
+    ///a transaction/ECDH key shared between multiple outputs, and the index of this output.
+    ///
+    ///This is used to receive payments created by `send_multiple`.
+    ///
+    ///Returns `Some((EnoteKeys, payment ID))` if the enote belongs to these keys, or `None` if not.
+    pub fn receive_with_index(
+        &self, recipient: &Recipient, commitment: &Commitment, transaction_key: RistrettoPoint, index: u64
+    ) -> Option<(EnoteKeys, Option<PaymentId>)> {
+        return self.receive_internal(recipient, commitment, transaction_key, Some(index))
     }
 
     ///Internal receiving functionality
     fn receive_internal(
-        &self, recipient: &Recipient, commitment: &Commitment, transaction_key: RistrettoPoint
-    ) -> Option<EnoteKeys> {
+        &self, recipient: &Recipient, commitment: &Commitment, transaction_key: RistrettoPoint, index: Option<u64>
+    ) -> Option<(EnoteKeys, Option<PaymentId>)> {
         //check view tag
         let shared_secret = self.shared_secret(&transaction_key);
+        let shared_secret = match index {
+            Some(index) => shared_secret.with_output_index(index),
+            None => shared_secret
+        };
         if shared_secret.get_view_tag() != recipient.view_tag {
             return None
         }
 
-        //check public key
-        let owner = self.derive_key(shared_secret.clone());
-        if &owner * G != recipient.public_key {
-            return None
-        }
+        //check public key and commitment
+        let (owner, _, value, blinding) = shared_secret.open_enote(recipient, commitment, |offset| {
+            let owner = self.spend + offset;
+            (&owner * G == recipient.public_key).then_some(owner)
+        })?;
 
-        //check commitment
-        let value = shared_secret.decrypt_amount(recipient.encrypted_amount);
-        let blinding = shared_secret.as_scalar();
-        if Commitment::commit(value, blinding) != *commitment {
-            return None
-        }
+        let payment_id = recipient.encrypted_payment_id
+            .map(|encrypted| shared_secret.decrypt_payment_id(encrypted));
 
-        return Some(EnoteKeys{
+        return Some((EnoteKeys{
             owner,
             value,
             blinding
-        })
+        }, payment_id))
     }
 
 } impl Drop for CryptoNotePrivate {
@@ -164,10 +194,18 @@ pub struct CryptoNotePrivateView {
         return SharedSecret::get(self.view, &other_public)
     }
 
-    ///Deterministically derive a unique ephemeral private key given a shared secret.
+    ///Deterministically derive a unique ephemeral public key given a shared secret.
     ///**These keys should never be reused.**
+    ///
+    ///This uses the legacy derivation, see `derive_key_with_version` for new enotes.
     pub fn derive_key(&self, shared_secret: SharedSecret) -> RistrettoPoint {
-        return self.spend + (&shared_secret.as_scalar() * G)
+        return self.derive_key_with_version(shared_secret, DerivationVersion::Legacy)
+    }
+
+    ///Deterministically derive a unique ephemeral public key given a shared secret and derivation version.
+    ///**These keys should never be reused.**
+    pub fn derive_key_with_version(&self, shared_secret: SharedSecret, version: DerivationVersion) -> RistrettoPoint {
+        return self.spend + (&shared_secret.key_offset(version) * G)
     }
 
     ///Create a new viewing keypair from the private view key and public spend key
@@ -180,46 +218,60 @@ pub struct CryptoNotePrivateView {
 
     ///"Receive" a payment, decrypting its content, given the pedersen commitment.
     ///
-    ///Returns the amount and blinding factor of the pedersen commitment if the enote belongs to these keys, or `None` if not.
-    pub fn receive(&self, recipient: &Recipient, commitment: &Commitment) -> Option<(u64, Scalar)> {
+    ///Returns the amount and blinding factor of the pedersen commitment, and the payment ID if any,
+    ///if the enote belongs to these keys, or `None` if not.
+    pub fn receive(&self, recipient: &Recipient, commitment: &Commitment) -> Option<(u64, Scalar, Option<PaymentId>)> {
         if let Some(transaction_key) = recipient.transaction_key {
-            return self.receive_internal(recipient, commitment, transaction_key)
+            return self.receive_internal(recipient, commitment, transaction_key, None)
         };
         return None
     }
 
     ///"Receive" a payment, decrypting its content, given the pedersen commitment and a transaction/ECDH key.
     ///
-    ///Returns the amount and blinding factor of the pedersen commitment if the enote belongs to these keys, or `None` if not.
+    ///Returns the amount and blinding factor of the pedersen commitment, and the payment ID if any,
+    ///if the enote belongs to these keys, or `None` if not.
     pub fn receive_with_key(
         &self, recipient: &Recipient, commitment: &Commitment, transaction_key: RistrettoPoint,
-    ) -> Option<(u64, Scalar)> {
-        return self.receive_internal(recipient, commitment, transaction_key)
+    ) -> Option<(u64, Scalar, Option<PaymentId>)> {
+        return self.receive_internal(recipient, commitment, transaction_key, None)
+    }
+
+    ///"Receive" a payment, decrypting its content, given the pedersen commitment,
+    ///a transaction/ECDH key shared between multiple outputs, and the index of this output.
+    ///
+    ///This is used to receive payments created by `send_multiple`.
+    ///
+    ///Returns the amount and blinding factor of the pedersen commitment, and the payment ID if any,
+    ///if the enote belongs to these keys, or `None` if not.
+    pub fn receive_with_index(
+        &self, recipient: &Recipient, commitment: &Commitment, transaction_key: RistrettoPoint, index: u64
+    ) -> Option<(u64, Scalar, Option<PaymentId>)> {
+        return self.receive_internal(recipient, commitment, transaction_key, Some(index))
     }
 
     ///Internal receiving functionality
     fn receive_internal(
-        &self, recipient: &Recipient,commitment: &Commitment, transaction_key: RistrettoPoint,
-    ) -> Option<(u64, Scalar)> {
+        &self, recipient: &Recipient,commitment: &Commitment, transaction_key: RistrettoPoint, index: Option<u64>
+    ) -> Option<(u64, Scalar, Option<PaymentId>)> {
         //check view tag
         let shared_secret = self.shared_secret(&transaction_key);
+        let shared_secret = match index {
+            Some(index) => shared_secret.with_output_index(index),
+            None => shared_secret
+        };
         if shared_secret.get_view_tag() != recipient.view_tag {
             return None
         }
 
-        //check public key
-        if self.derive_key(shared_secret.clone()) != recipient.public_key {
-            return None
-        }
+        //check public key and commitment
+        let (_, _, value, blinding) = shared_secret.open_enote(recipient, commitment, |offset|
+            (self.spend + (offset * G) == recipient.public_key).then_some(()))?;
 
-        //check commitment
-        let value = shared_secret.decrypt_amount(recipient.encrypted_amount);
-        let blinding = shared_secret.as_scalar();
-        if Commitment::commit(value, blinding) != *commitment {
-            return None
-        }
+        let payment_id = recipient.encrypted_payment_id
+            .map(|encrypted| shared_secret.decrypt_payment_id(encrypted));
 
-        return Some((value, blinding))
+        return Some((value, blinding, payment_id))
     }
 
 } impl Zeroize for CryptoNotePrivateView {
@@ -268,8 +320,16 @@ pub struct CryptoNotePublic {
 
     ///Derive the unique ephemeral public key given a shared secret.
     ///**These keys should never be reused.**
+    ///
+    ///This uses the legacy derivation, see `derive_key_with_version` for new enotes.
     pub fn derive_key(&self, shared_secret: SharedSecret) -> RistrettoPoint {
-        return self.spend + (&shared_secret.as_scalar() * G)
+        return self.derive_key_with_version(shared_secret, DerivationVersion::Legacy)
+    }
+
+    ///Derive the unique ephemeral public key given a shared secret and derivation version.
+    ///**These keys should never be reused.**
+    pub fn derive_key_with_version(&self, shared_secret: SharedSecret, version: DerivationVersion) -> RistrettoPoint {
+        return self.spend + (&shared_secret.key_offset(version) * G)
     }
 
     ///"Send" to this address, where only the recipient can detect that the payment is for them.
@@ -283,7 +343,7 @@ pub struct CryptoNotePublic {
         let seed = batch_encode_points(&vec!(self.view, self.spend)).concat();
         let seed = h_scalar(&[seed, amount.to_le_bytes().to_vec()].concat());
         let key = seed + Scalar::generate();
-        self.send_internal(amount, key, true)
+        self.send_internal(amount, key, true, None, None)
     }
 
     ///"Send" to this address, given a transaction/ECDH key,
@@ -295,26 +355,62 @@ pub struct CryptoNotePublic {
     ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
     ///and the public data for the receiver to detect the payment.
     pub fn send_with_key(&self, amount: u64, transaction_key: Scalar) -> (Scalar, Recipient) {
-        self.send_internal(amount, transaction_key, false)
+        self.send_internal(amount, transaction_key, false, None, None)
+    }
+
+    ///"Send" to this address, given a transaction/ECDH key shared between multiple outputs,
+    ///and the index of this output, where only the recipient can detect that the payment is for them.
+    ///
+    ///Note that `receive_with_index` must be used to receive payments created by this method.
+    ///To send to multiple addresses at once, use `send_multiple` instead.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send_with_index(&self, amount: u64, transaction_key: Scalar, index: u64) -> (Scalar, Recipient) {
+        self.send_internal(amount, transaction_key, false, None, Some(index))
+    }
+
+    ///"Send" to this address, where only the recipient can detect that the payment is for them,
+    ///and only the sender can recover the payment later, using their outgoing view key.
+    ///
+    ///The transaction/ECDH key is derived from the outgoing view key, see `OutgoingViewKey::recover`.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send_with_outgoing(&self, amount: u64, outgoing: &OutgoingViewKey) -> (Scalar, Recipient) {
+        let (nonce, transaction_key) = outgoing.generate_transaction_key();
+        let (blinding, mut recipient) = self.send_internal(amount, transaction_key, true, None, None);
+        outgoing.encrypt_destination(nonce, &self.view, &mut recipient);
+        return (blinding, recipient)
     }
 
     ///Internal sending functionality
-    fn send_internal(&self, amount: u64, transaction_sk: Scalar, include_txn_key: bool) -> (Scalar, Recipient) {
+    fn send_internal(
+        &self, amount: u64, transaction_sk: Scalar, include_txn_key: bool, payment_id: Option<PaymentId>, index: Option<u64>
+    ) -> (Scalar, Recipient) {
         let transaction_key = match include_txn_key {
             true => Some(&transaction_sk * G),
             false => None
         };
 
         let shared_secret = self.shared_secret(transaction_sk);
+        let shared_secret = match index {
+            Some(index) => shared_secret.with_output_index(index),
+            None => shared_secret
+        };
         let view_tag = shared_secret.get_view_tag();
-        let encrypted_amount = shared_secret.encrypt_amount(amount);
-        let blinding = shared_secret.as_scalar();
+        let encrypted_amount = shared_secret.encrypt_amount_with_version(amount, DerivationVersion::CURRENT);
+        let encrypted_payment_id = payment_id.map(|payment_id| shared_secret.encrypt_payment_id(payment_id));
+        let blinding = shared_secret.blinding(DerivationVersion::CURRENT);
 
         let recipient = Recipient {
-            public_key: self.derive_key(shared_secret),
+            public_key: self.derive_key_with_version(shared_secret, DerivationVersion::CURRENT),
             transaction_key,
             view_tag,
-            encrypted_amount
+            encrypted_amount,
+            encrypted_payment_id,
+            auxiliary_key: None,
+            encrypted_destination: None
         };
         return (blinding, recipient)
     }
@@ -334,4 +430,79 @@ pub struct CryptoNotePublic {
             spend: RistrettoPoint::from_bytes(&bytes[32..64])?
         })
     }
-}
\ No newline at end of file
+}
+
+
+///CryptoNote address with a payment ID.
+///
+///Payments sent to this address include the payment ID, encrypted so that only the recipient can read it.
+///This can be used to match incoming payments with invoices, without creating a new address for each one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IntegratedAddress {
+    pub address: CryptoNotePublic,
+    pub payment_id: PaymentId
+
+} impl IntegratedAddress {
+    ///Create an integrated address from an address and a payment ID.
+    pub fn new(address: CryptoNotePublic, payment_id: PaymentId) -> Self {
+        return Self{address, payment_id}
+    }
+
+    ///Create an integrated address with a random payment ID.
+    pub fn generate(address: CryptoNotePublic) -> Self {
+        return Self::new(address, thread_rng().gen())
+    }
+
+    ///"Send" to this address, where only the recipient can detect that the payment is for them.
+    ///
+    ///The transaction/ECDH key is generated automatically.
+    ///Use `send_with_key` instead to manually input a transaction key.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send(&self, amount: u64) -> (Scalar, Recipient) {
+        let seed = batch_encode_points(&vec!(self.address.view, self.address.spend)).concat();
+        let seed = h_scalar(&[seed, amount.to_le_bytes().to_vec(), self.payment_id.to_vec()].concat());
+        let key = seed + Scalar::generate();
+        self.address.send_internal(amount, key, true, Some(self.payment_id), None)
+    }
+
+    ///"Send" to this address, given a transaction/ECDH key,
+    ///where only the recipient can detect that the payment is for them.
+    ///
+    ///Note that `receive_with_key` must be used to receive payments created by this method.
+    ///For automatic transaction key generation, used `send` instead.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send_with_key(&self, amount: u64, transaction_key: Scalar) -> (Scalar, Recipient) {
+        self.address.send_internal(amount, transaction_key, false, Some(self.payment_id), None)
+    }
+
+    ///"Send" to this address, given a transaction/ECDH key shared between multiple outputs,
+    ///and the index of this output, where only the recipient can detect that the payment is for them.
+    ///
+    ///Note that `receive_with_index` must be used to receive payments created by this method.
+    ///To send to multiple addresses at once, use `send_multiple` instead.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send_with_index(&self, amount: u64, transaction_key: Scalar, index: u64) -> (Scalar, Recipient) {
+        self.address.send_internal(amount, transaction_key, false, Some(self.payment_id), Some(index))
+    }
+
+    ///"Send" to this address, where only the recipient can detect that the payment is for them,
+    ///and only the sender can recover the payment later, using their outgoing view key.
+    ///
+    ///The transaction/ECDH key is derived from the outgoing view key, see `OutgoingViewKey::recover`.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send_with_outgoing(&self, amount: u64, outgoing: &OutgoingViewKey) -> (Scalar, Recipient) {
+        let (nonce, transaction_key) = outgoing.generate_transaction_key();
+        let (blinding, mut recipient) = self.address.send_internal(amount, transaction_key, true, Some(self.payment_id), None);
+        outgoing.encrypt_destination(nonce, &self.address.view, &mut recipient);
+        return (blinding, recipient)
+    }
+
+} impl ToBytes<'_> for IntegratedAddress {}
@@ -8,6 +8,29 @@
 
 use crate::internal_common::*;
 use zeroize::Zeroize;
+use super::{
+    PaymentId,
+    Recipient
+};
+
+
+///Version of the scheme for deriving an enote's one-time key offset, blinding factor, and amount encryption key
+///from its shared secret.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DerivationVersion {
+    ///The key offset and blinding factor are both the shared secret as a scalar (see `SharedSecret::as_scalar`).
+    ///
+    ///This is only used to receive old enotes.
+    Legacy,
+    ///The key offset, blinding factor, and amount encryption key each use their own hash domain.
+    V1
+
+} impl DerivationVersion {
+    ///The version used for new enotes.
+    pub const CURRENT: Self = Self::V1;
+    ///All versions which can be received, newest first.
+    pub const ALL: [Self; 2] = [Self::V1, Self::Legacy];
+}
 
 
 ///Shared secret between two keys, `A` and `B`.
@@ -29,6 +52,11 @@ pub struct SharedSecret(
         return Self(point.compress().to_bytes())
     }
 
+    ///Import compressed point
+    pub(crate) fn from_compressed(point: CompressedRistretto) -> Self {
+        return Self(point.to_bytes())
+    }
+
     ///Given a user's one-time private key (`my_private`),
     ///and another user's public key (`other_public`),
     ///create a unique one-time shared secret that only those 2 users know.
@@ -36,6 +64,14 @@ pub struct SharedSecret(
         return Self::from_point(&(my_private * other_public))
     }
 
+    ///Derive the shared secret of an output, given its index in the transaction.
+    ///
+    ///This is needed when one transaction key is shared between multiple outputs,
+    ///so that outputs to the same address don't have the same keys.
+    pub fn with_output_index(&self, index: u64) -> Self {
+        return Self(domain_h_bytes(&[self.0.as_slice(), &index.to_le_bytes()].concat(), domains::ECDH_OUTPUT_INDEX))
+    }
+
     ///Calculate the view tag associated with this shared secret.
     pub fn get_view_tag(&self) -> ViewTag {
         return domain_h_bytes(&self.0, domains::ECDH_VIEW_TAG)[0]
@@ -46,17 +82,92 @@ pub struct SharedSecret(
         return Scalar::from_bytes_mod_order(self.0)
     }
 
-    ///Encrypt an amount (`u64`) with this shared secret.
+    ///Calculate the offset which is added to the recipient's spend key to derive the one-time key.
+    pub fn key_offset(&self, version: DerivationVersion) -> Scalar {
+        return match version {
+            DerivationVersion::Legacy => self.as_scalar(),
+            DerivationVersion::V1 => domain_h_scalar(&self.0, domains::ECDH_KEY_OFFSET)
+        }
+    }
+
+    ///Calculate the blinding factor of the enote's pedersen commitment.
+    pub fn blinding(&self, version: DerivationVersion) -> Scalar {
+        return match version {
+            DerivationVersion::Legacy => self.as_scalar(),
+            DerivationVersion::V1 => domain_h_scalar(&self.0, domains::ECDH_BLINDING)
+        }
+    }
+
+    ///Encrypt an amount (`u64`) with this shared secret, using the legacy derivation.
+    ///
+    ///Use `encrypt_amount_with_version` for new enotes.
     pub fn encrypt_amount(&self, amount: u64) -> u64 {
+        return self.encrypt_amount_with_version(amount, DerivationVersion::Legacy)
+    }
+
+    ///Decrypt an amount (`u64`) with this shared secret, using the legacy derivation.
+    ///
+    ///Use `decrypt_amount_with_version` to receive new enotes.
+    pub fn decrypt_amount(&self, encrypted_amount: u64) -> u64 {
+        self.encrypt_amount(encrypted_amount)
+    }
+
+    ///Encrypt an amount (`u64`) with this shared secret.
+    pub fn encrypt_amount_with_version(&self, amount: u64, version: DerivationVersion) -> u64 {
+        let domain = match version {
+            DerivationVersion::Legacy => domains::ECDH_ENCRYPTION_KEY,
+            DerivationVersion::V1 => domains::ECDH_AMOUNT_KEY
+        };
         //amount is XOR'ed
         return amount ^ u64::from_be_bytes(
-            domain_h_bytes(&self.0, domains::ECDH_ENCRYPTION_KEY)[0..8]
+            domain_h_bytes(&self.0, domain)[0..8]
             .try_into().expect("Failed to convert shared secret to u64 encryption key"))
     }
 
     ///Decrypt an amount (`u64`) with this shared secret.
-    pub fn decrypt_amount(&self, encrypted_amount: u64) -> u64 {
-        self.encrypt_amount(encrypted_amount)
+    pub fn decrypt_amount_with_version(&self, encrypted_amount: u64, version: DerivationVersion) -> u64 {
+        self.encrypt_amount_with_version(encrypted_amount, version)
+    }
+
+    ///Find the derivation version of an enote, given a check of its one-time key offset,
+    ///then decrypt its amount and check its commitment.
+    ///
+    ///Returns the result of `check_key`, the version, and the enote's amount and blinding factor,
+    ///or `None` if the key check fails for all versions, or the commitment is invalid.
+    pub(crate) fn open_enote<T>(
+        &self, recipient: &Recipient, commitment: &Commitment, check_key: impl Fn(&Scalar) -> Option<T>
+    ) -> Option<(T, DerivationVersion, u64, Scalar)> {
+        for version in DerivationVersion::ALL {
+            let checked = match check_key(&self.key_offset(version)) {
+                Some(checked) => checked,
+                None => continue
+            };
+
+            //check commitment
+            let value = self.decrypt_amount_with_version(recipient.encrypted_amount, version);
+            let blinding = self.blinding(version);
+            if Commitment::commit(value, blinding) != *commitment {
+                return None
+            }
+            return Some((checked, version, value, blinding))
+        }
+        return None
+    }
+
+    ///Encrypt a payment ID with this shared secret.
+    pub fn encrypt_payment_id(&self, payment_id: PaymentId) -> PaymentId {
+        //payment ID is XOR'ed
+        let key = domain_h_bytes(&self.0, domains::ECDH_PAYMENT_ID);
+        let mut encrypted = payment_id;
+        for (byte, key) in encrypted.iter_mut().zip(key) {
+            *byte ^= key;
+        }
+        return encrypted
+    }
+
+    ///Decrypt a payment ID with this shared secret.
+    pub fn decrypt_payment_id(&self, encrypted_payment_id: PaymentId) -> PaymentId {
+        self.encrypt_payment_id(encrypted_payment_id)
     }
 
 } impl Drop for SharedSecret {
@@ -117,6 +228,8 @@ pub trait ECDHPrivateKey {
 
     ///Deterministically derive a unique ephemeral private key given a shared secret.
     ///**These keys should never be reused.**
+    ///
+    ///This uses the legacy derivation, see `SharedSecret::key_offset` for new enotes.
     fn derive_key(&self, shared_secret: SharedSecret) -> Self {
         return self + shared_secret.as_scalar()
     }
@@ -142,12 +255,16 @@ pub trait ECDHPublicKey {
 
     ///Deterministically derive a unique ephemeral public key given a shared secret and a custom basepoint.
     ///**These keys should never be reused.**
+    ///
+    ///This uses the legacy derivation, see `SharedSecret::key_offset` for new enotes.
     fn derive_key_with_base(&self, shared_secret: SharedSecret, base: RistrettoPoint) -> Self {
         return self + (shared_secret.as_scalar() * base)
     }
 
     ///Derive the unique ephemeral public key given a shared secret.
     ///**These keys should never be reused.**
+    ///
+    ///This uses the legacy derivation, see `SharedSecret::key_offset` for new enotes.
     fn derive_key(&self, shared_secret: SharedSecret) -> Self {
         return self + (&shared_secret.as_scalar() * G)
     }
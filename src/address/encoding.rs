@@ -0,0 +1,180 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Human-readable (base58) address encoding.
+//!
+//! An encoded address consists of a network prefix, an address type tag,
+//! the public view and spend keys, the payment ID (for integrated addresses), and a 4 byte checksum.
+
+use crate::internal_common::*;
+use super::{
+    Address,
+    PaymentId,
+    cryptonote::{
+        CryptoNotePublic,
+        IntegratedAddress
+    },
+    subaddress::SubaddressPublic
+};
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const CHECKSUM_SIZE: usize = 4;
+const KEYS_SIZE: usize = 64;
+const PAYMENT_ID_SIZE: usize = std::mem::size_of::<PaymentId>();
+
+///Address type tags
+const TYPE_CRYPTONOTE: u8 = 0;
+const TYPE_SUBADDRESS: u8 = 1;
+const TYPE_INTEGRATED: u8 = 2;
+
+///Encode bytes in base58.
+fn base58_encode(bytes: &[u8]) -> String {
+    //base 58 digits, least significant first
+    let mut digits: Vec<u8> = Vec::new();
+    for byte in bytes {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    //leading zero bytes are encoded as the first character
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    return std::iter::repeat_n(ALPHABET[0], zeros)
+        .chain(digits.iter().rev().map(|digit| ALPHABET[*digit as usize]))
+        .map(char::from).collect()
+}
+
+///Decode a base58 string.
+fn base58_decode(encoded: &str) -> Result<Vec<u8>, AddressError> {
+    //base 256 digits, least significant first
+    let mut bytes: Vec<u8> = Vec::new();
+    for character in encoded.bytes() {
+        let mut carry = ALPHABET.iter().position(|c| *c == character)
+            .ok_or(AddressError::InvalidCharacter)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let zeros = encoded.bytes().take_while(|c| *c == ALPHABET[0]).count();
+    bytes.extend(std::iter::repeat_n(0, zeros));
+    bytes.reverse();
+    return Ok(bytes)
+}
+
+///Calculate the checksum of an encoded address (without the checksum).
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_SIZE] {
+    let mut checksum = [0; CHECKSUM_SIZE];
+    checksum.copy_from_slice(&domain_h_bytes(bytes, domains::ADDRESS_CHECKSUM)[0..CHECKSUM_SIZE]);
+    return checksum
+}
+
+///Decode a public key.
+fn decode_key(bytes: &[u8]) -> Result<RistrettoPoint, AddressError> {
+    return CompressedRistretto::from_slice(bytes).decompress().ok_or(AddressError::InvalidKey)
+}
+
+
+///The network an address is used on.
+///
+///Addresses of different networks have different prefixes,
+///to prevent sending to an address on the wrong network.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Stagenet
+
+} impl Network {
+    ///Return the prefix of addresses on this network.
+    pub fn prefix(&self) -> u8 {
+        return match self {
+            Self::Mainnet => 0x52,
+            Self::Testnet => 0x54,
+            Self::Stagenet => 0x53
+        }
+    }
+
+    ///Return the network with the given address prefix, if any.
+    pub fn from_prefix(prefix: u8) -> Option<Self> {
+        return [Self::Mainnet, Self::Testnet, Self::Stagenet].into_iter()
+            .find(|network| network.prefix() == prefix)
+    }
+}
+
+
+impl Address {
+    ///Encode this address as a base58 string, for use on `network`.
+    pub fn encode(&self, network: Network) -> String {
+        let (address_type, view, spend, payment_id) = match self {
+            Self::CryptoNote(address) => (TYPE_CRYPTONOTE, address.view, address.spend, None),
+            Self::Subaddress(address) => (TYPE_SUBADDRESS, address.view, address.spend, None),
+            Self::Integrated(address) =>
+                (TYPE_INTEGRATED, address.address.view, address.address.spend, Some(address.payment_id))
+        };
+
+        let mut bytes = vec!(network.prefix(), address_type);
+        bytes.extend(view.compress().as_bytes());
+        bytes.extend(spend.compress().as_bytes());
+        if let Some(payment_id) = payment_id {
+            bytes.extend(payment_id);
+        }
+        bytes.extend(checksum(&bytes));
+        return base58_encode(&bytes)
+    }
+
+    ///Decode a base58 address, which must be for use on `network`.
+    ///
+    ///Returns `AddressError::WrongNetwork` if the address is for another network,
+    ///or another `AddressError` if the address is invalid or has a typo.
+    pub fn decode(encoded: &str, network: Network) -> Result<Self, AddressError> {
+        let bytes = base58_decode(encoded)?;
+        if bytes.len() < 2 + KEYS_SIZE + CHECKSUM_SIZE {
+            return Err(AddressError::InvalidLength)
+        }
+
+        let (data, check) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+        if checksum(data) != check {
+            return Err(AddressError::InvalidChecksum)
+        }
+        if data[0] != network.prefix() {
+            return Err(AddressError::WrongNetwork)
+        }
+
+        let extra_size = match data[1] {
+            TYPE_CRYPTONOTE | TYPE_SUBADDRESS => 0,
+            TYPE_INTEGRATED => PAYMENT_ID_SIZE,
+            _ => return Err(AddressError::UnknownType)
+        };
+        if data.len() != 2 + KEYS_SIZE + extra_size {
+            return Err(AddressError::InvalidLength)
+        }
+
+        let view = decode_key(&data[2..34])?;
+        let spend = decode_key(&data[34..66])?;
+        return Ok(match data[1] {
+            TYPE_CRYPTONOTE => Self::CryptoNote(CryptoNotePublic{view, spend}),
+            TYPE_SUBADDRESS => Self::Subaddress(SubaddressPublic{view, spend}),
+            _ => Self::Integrated(IntegratedAddress::new(
+                CryptoNotePublic{view, spend},
+                data[66..].try_into().expect("Failed to convert payment ID")
+            ))
+        })
+    }
+}
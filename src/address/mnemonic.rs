@@ -0,0 +1,120 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Mnemonic seed phrases, for backing up the seed of a wallet's keys as words.
+//!
+//! A 32 byte seed and an 8 bit checksum are encoded as 24 words from the BIP-39 English wordlist,
+//! with 11 bits per word, so the last word includes the checksum.
+//! Note that the checksum and passphrase extension use this crate's hash functions,
+//! so phrases are not compatible with BIP-39 wallets.
+
+use rand::{thread_rng, Rng};
+use zeroize::Zeroize;
+
+use crate::internal_common::*;
+use super::wordlist::ENGLISH;
+
+///The number of words in a mnemonic seed phrase.
+pub const MNEMONIC_WORDS: usize = 24;
+const BITS_PER_WORD: usize = 11;
+
+///Calculate the checksum of a seed.
+fn checksum(seed: &[u8; 32]) -> u8 {
+    return domain_h_bytes(seed, domains::MNEMONIC_CHECKSUM)[0]
+}
+
+
+///A mnemonic seed phrase.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Zeroize)]
+pub struct Mnemonic {
+    seed: [u8; 32]
+
+} impl Mnemonic {
+    ///Create a mnemonic for a seed.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        return Self{seed}
+    }
+
+    ///Generate a mnemonic for a new random seed.
+    pub fn generate() -> Self {
+        return Self::from_seed(thread_rng().gen())
+    }
+
+    ///Parse a seed phrase, with the words separated by whitespace.
+    ///
+    ///Returns `MnemonicError` if the phrase is not valid, for example due to a typo.
+    pub fn from_phrase(phrase: &str) -> Result<Self, MnemonicError> {
+        let words: Vec<String> = phrase.split_whitespace().map(|word| word.to_lowercase()).collect();
+        if words.len() != MNEMONIC_WORDS {
+            return Err(MnemonicError::InvalidWordCount)
+        }
+
+        //33 bytes: the seed, then the checksum
+        let mut bytes = [0u8; 33];
+        for (i, word) in words.iter().enumerate() {
+            let index = ENGLISH.binary_search(&word.as_str())
+                .map_err(|_| MnemonicError::UnknownWord(i))?;
+            for bit in 0..BITS_PER_WORD {
+                if (index >> (BITS_PER_WORD - 1 - bit)) & 1 == 1 {
+                    let position = (i * BITS_PER_WORD) + bit;
+                    bytes[position / 8] |= 0x80 >> (position % 8);
+                }
+            }
+        }
+
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&bytes[0..32]);
+        let valid = checksum(&seed) == bytes[32];
+        bytes.zeroize();
+        if !valid {
+            seed.zeroize();
+            return Err(MnemonicError::InvalidChecksum)
+        }
+        return Ok(Self{seed})
+    }
+
+    ///Return the words of this seed phrase.
+    pub fn to_words(&self) -> Vec<&'static str> {
+        let mut bytes = [0u8; 33];
+        bytes[0..32].copy_from_slice(&self.seed);
+        bytes[32] = checksum(&self.seed);
+
+        let mut words: Vec<&'static str> = Vec::new();
+        for i in 0..MNEMONIC_WORDS {
+            let mut index = 0usize;
+            for bit in 0..BITS_PER_WORD {
+                let position = (i * BITS_PER_WORD) + bit;
+                index = (index << 1) | ((bytes[position / 8] >> (7 - (position % 8))) & 1) as usize;
+            }
+            words.push(ENGLISH[index]);
+        }
+        bytes.zeroize();
+        return words
+    }
+
+    ///Return this seed phrase, with the words separated by spaces.
+    pub fn to_phrase(&self) -> String {
+        return self.to_words().join(" ")
+    }
+
+    ///Return the seed, for use with `from_seed` methods.
+    pub fn to_seed(&self) -> [u8; 32] {
+        return self.seed
+    }
+
+    ///Return the seed extended with a passphrase, for use with `from_seed` methods.
+    ///
+    ///Different passphrases result in different (unrelated) seeds, and thus different keys.
+    pub fn to_seed_with_passphrase(&self, passphrase: &str) -> [u8; 32] {
+        return domain_h_bytes(&[&self.seed, passphrase.as_bytes()].concat(), domains::MNEMONIC_PASSPHRASE)
+    }
+
+} impl Drop for Mnemonic {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+
+} impl ToBytes<'_> for Mnemonic {}
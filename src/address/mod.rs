@@ -5,15 +5,34 @@
  */
 
 mod ecdh;
+mod wordlist;
 use crate::internal_common::*;
 
 pub use ecdh::{
     ECDHPrivateKey,
     ECDHPublicKey,
-    SharedSecret
+    SharedSecret,
+    DerivationVersion
 };
 pub mod cryptonote;
 pub mod subaddress;
+pub mod encoding;
+pub mod mnemonic;
+pub mod scanner;
+pub mod outgoing;
+
+use cryptonote::{
+    CryptoNotePublic,
+    IntegratedAddress
+};
+use subaddress::SubaddressPublic;
+use outgoing::{
+    OutgoingViewKey,
+    EncryptedDestination
+};
+
+///A short ID attached to a payment, for example to match it with an invoice.
+pub type PaymentId = [u8; 8];
 
 ///A recipient in a transaction.
 ///Contains the public key, as well as the necessary information for the recipient to retrieve the private keys.
@@ -29,7 +48,16 @@ pub struct Recipient {
     ///View tag
     pub view_tag: ViewTag,
     ///Encrypted amount which only the sender and receiver can decrypt
-    pub encrypted_amount: u64
+    pub encrypted_amount: u64,
+    ///Encrypted payment ID which only the sender and receiver can decrypt,
+    ///if sent to an integrated address
+    pub encrypted_payment_id: Option<PaymentId>,
+    ///Auxiliary transaction key, which protects against the Janus attack,
+    ///if sent with `SubaddressPublic::send_hardened`
+    pub auxiliary_key: Option<RistrettoPoint>,
+    ///Encrypted destination which only the sender can decrypt,
+    ///if sent with `send_with_outgoing`
+    pub encrypted_destination: Option<EncryptedDestination>
 
 } impl Recipient {
     pub fn to_enote(&self, commitment: &Commitment) -> Enote {
@@ -39,4 +67,94 @@ pub struct Recipient {
         }
     }
 
-} impl ToBytes<'_> for Recipient {}
\ No newline at end of file
+} impl ToBytes<'_> for Recipient {}
+
+///A public address which can be sent to, regardless of the address protocol.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Address {
+    ///CryptoNote-style address
+    CryptoNote(CryptoNotePublic),
+    ///Monero-like subaddress
+    Subaddress(SubaddressPublic),
+    ///CryptoNote-style address with a payment ID
+    Integrated(IntegratedAddress)
+
+} impl Address {
+    ///"Send" to this address, where only the recipient can detect that the payment is for them.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send(&self, amount: u64) -> (Scalar, Recipient) {
+        return match self {
+            Self::CryptoNote(address) => address.send(amount),
+            Self::Subaddress(address) => address.send(amount),
+            Self::Integrated(address) => address.send(amount)
+        }
+    }
+
+    ///"Send" to this address, given a transaction/ECDH key shared between multiple outputs,
+    ///and the index of this output, where only the recipient can detect that the payment is for them.
+    ///
+    ///Subaddresses can't use a shared transaction key,
+    ///so payments to subaddresses are sent with their own transaction key, as with `send`.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send_with_index(&self, amount: u64, transaction_key: Scalar, index: u64) -> (Scalar, Recipient) {
+        return match self {
+            Self::CryptoNote(address) => address.send_with_index(amount, transaction_key, index),
+            Self::Subaddress(address) => address.send(amount),
+            Self::Integrated(address) => address.send_with_index(amount, transaction_key, index)
+        }
+    }
+
+    ///"Send" to this address, where only the recipient can detect that the payment is for them,
+    ///and only the sender can recover the payment later, using their outgoing view key.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send_with_outgoing(&self, amount: u64, outgoing: &OutgoingViewKey) -> (Scalar, Recipient) {
+        return match self {
+            Self::CryptoNote(address) => address.send_with_outgoing(amount, outgoing),
+            Self::Subaddress(address) => address.send_with_outgoing(amount, outgoing),
+            Self::Integrated(address) => address.send_with_outgoing(amount, outgoing)
+        }
+    }
+
+} impl From<CryptoNotePublic> for Address {
+    fn from(address: CryptoNotePublic) -> Self {
+        return Self::CryptoNote(address)
+    }
+
+} impl From<SubaddressPublic> for Address {
+    fn from(address: SubaddressPublic) -> Self {
+        return Self::Subaddress(address)
+    }
+
+} impl From<IntegratedAddress> for Address {
+    fn from(address: IntegratedAddress) -> Self {
+        return Self::Integrated(address)
+    }
+
+} impl ToBytes<'_> for Address {}
+
+///The private and public shared transaction key, and the blinding factor and public data of each output,
+///see `send_multiple`.
+pub type SharedKeyOutputs = (Scalar, RistrettoPoint, Vec<(Scalar, Recipient)>);
+
+///"Send" to multiple addresses, with one transaction/ECDH key shared between all outputs,
+///where only each recipient can detect which payment is for them.
+///
+///The index of each output is included in its key derivation,
+///so `receive_with_index` must be used to receive these payments.
+///Payments to subaddresses are sent with their own transaction key instead, see `Address::send_with_index`.
+///
+///Returns the private and public shared transaction key (the public key must be included with the outputs),
+///and the blinding factor of each pedersen commitment and the public data for each receiver, in order.
+pub fn send_multiple(outputs: &[(Address, u64)]) -> SharedKeyOutputs {
+    let transaction_key = Scalar::generate();
+    let sent = outputs.iter().enumerate()
+        .map(|(index, (address, amount))| address.send_with_index(*amount, transaction_key, index as u64))
+        .collect();
+    return (transaction_key, &transaction_key * G, sent)
+}
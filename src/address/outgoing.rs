@@ -0,0 +1,140 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Outgoing view keys, for recovering sent payments.
+//!
+//! Payments sent with `send_with_outgoing` use a transaction key derived from the sender's outgoing view key
+//! and a random nonce, and include the nonce and the destination's view key, encrypted with the outgoing view key.
+//! The outgoing view key is derived from the private spend key, so a wallet restored from its seed
+//! can recover the destination, amount, and blinding factor of each payment it sent with `OutgoingViewKey::recover`.
+
+use rand::{thread_rng, Rng};
+use zeroize::Zeroize;
+
+use crate::internal_common::*;
+use super::{
+    Address,
+    Recipient,
+    ECDHPrivateKey,
+    cryptonote::{
+        CryptoNotePublic,
+        IntegratedAddress
+    },
+    subaddress::SubaddressPublic
+};
+
+///The data included with a payment, for its sender to recover it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EncryptedDestination {
+    ///Nonce, which the transaction key is derived from
+    pub nonce: [u8; 32],
+    ///Encrypted view key of the destination address
+    pub encrypted_view: [u8; 32]
+
+} impl ToBytes<'_> for EncryptedDestination {}
+
+///A payment recovered by its sender.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SentEnote {
+    ///The address the payment was sent to
+    pub destination: Address,
+    pub value: u64,
+    pub blinding: Scalar
+
+} impl ToBytes<'_> for SentEnote {}
+
+///Outgoing view key, for sending payments which can be recovered later.
+///
+///**This should not be publically shared.**
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Zeroize)]
+pub struct OutgoingViewKey(
+    Scalar
+
+); impl OutgoingViewKey {
+    ///Derive the outgoing view key of a private spend key.
+    pub(crate) fn from_spend(spend: &Scalar) -> Self {
+        return Self(domain_h_scalar(spend.as_bytes(), domains::OUTGOING_VIEW_KEY))
+    }
+
+    ///Derive the transaction key for a nonce.
+    fn transaction_key(&self, nonce: &[u8; 32]) -> Scalar {
+        return domain_h_scalar(&[self.0.as_bytes().as_slice(), nonce].concat(), domains::OUTGOING_TRANSACTION_KEY)
+    }
+
+    ///Calculate the key which encrypts the destination of a payment, given its one-time public key.
+    fn encryption_key(&self, public_key: &RistrettoPoint) -> [u8; 32] {
+        let msg = [self.0.as_bytes().as_slice(), public_key.compress().as_bytes()].concat();
+        return domain_h_bytes(&msg, domains::OUTGOING_ENCRYPTION_KEY)
+    }
+
+    ///Generate a random nonce, and the transaction key derived from it.
+    pub(crate) fn generate_transaction_key(&self) -> ([u8; 32], Scalar) {
+        let nonce: [u8; 32] = thread_rng().gen();
+        return (nonce, self.transaction_key(&nonce))
+    }
+
+    ///Include the nonce and destination view key with a payment.
+    pub(crate) fn encrypt_destination(&self, nonce: [u8; 32], view: &RistrettoPoint, recipient: &mut Recipient) {
+        let mut encrypted_view = view.compress().to_bytes();
+        for (byte, key) in encrypted_view.iter_mut().zip(self.encryption_key(&recipient.public_key)) {
+            *byte ^= key;
+        }
+        recipient.encrypted_destination = Some(EncryptedDestination{nonce, encrypted_view});
+    }
+
+    ///Recover a payment sent with this outgoing view key, given the pedersen commitment.
+    ///
+    ///Returns `Some(SentEnote)` if the payment was sent with this key, or `None` if not.
+    pub fn recover(&self, recipient: &Recipient, commitment: &Commitment) -> Option<SentEnote> {
+        let encrypted = recipient.encrypted_destination?;
+        let transaction_key = recipient.transaction_key?;
+
+        //decrypt the destination view key
+        let mut view = encrypted.encrypted_view;
+        for (byte, key) in view.iter_mut().zip(self.encryption_key(&recipient.public_key)) {
+            *byte ^= key;
+        }
+        let view = CompressedRistretto(view).decompress()?;
+
+        //check the transaction key, which is r * G for CryptoNote addresses, and r * D for subaddresses
+        let transaction_sk = self.transaction_key(&encrypted.nonce);
+        let shared_secret = transaction_sk.shared_secret(&view);
+        let ((spend, subaddress), _, value, blinding) = shared_secret.open_enote(recipient, commitment, |offset| {
+            let spend = recipient.public_key - (offset * G);
+            if transaction_key == &transaction_sk * G {
+                return Some((spend, false))
+            }
+            if transaction_key == transaction_sk * spend {
+                return Some((spend, true))
+            }
+            return None
+        })?;
+
+        let destination = match (subaddress, recipient.encrypted_payment_id) {
+            (true, _) => Address::Subaddress(SubaddressPublic{view, spend}),
+            (false, None) => Address::CryptoNote(CryptoNotePublic{view, spend}),
+            (false, Some(encrypted)) => Address::Integrated(IntegratedAddress::new(
+                CryptoNotePublic{view, spend},
+                shared_secret.decrypt_payment_id(encrypted)
+            ))
+        };
+        return Some(SentEnote{destination, value, blinding})
+    }
+
+} impl Drop for OutgoingViewKey {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+
+} impl ToBytes<'_> for OutgoingViewKey {
+    fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        return Ok(self.0.to_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        return Ok(Self(Scalar::from_bytes(bytes)?))
+    }
+}
@@ -0,0 +1,222 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Batch scanning of outputs with view keys.
+//!
+//! Scanning a large number of outputs is much faster with a `Scanner` than calling `receive` for each output:
+//! shared secrets are compressed in batches, outputs with a mismatching view tag are rejected early,
+//! and the outputs are split between multiple threads.
+
+use std::thread;
+
+use crate::internal_common::*;
+use super::{
+    Recipient,
+    SharedSecret,
+    DerivationVersion,
+    PaymentId,
+    cryptonote::CryptoNotePrivateView,
+    subaddress::MasterPrivateView
+};
+
+///Number of shared secrets compressed at once.
+const BATCH_SIZE: usize = 256;
+
+///Calculate the shared secrets of outputs,
+///and return the indices and shared secrets of the outputs with a matching view tag.
+///
+///Outputs without their own transaction key use the shared secret of the shared transaction key, if any,
+///derived with the index of the output.
+fn detect(
+    half_view: &Scalar, offset: usize, outputs: &[(Recipient, Commitment)], shared: Option<&SharedSecret>
+) -> Vec<(usize, SharedSecret)> {
+    let mut detected: Vec<(usize, SharedSecret)> = Vec::new();
+    for (batch_index, batch) in outputs.chunks(BATCH_SIZE).enumerate() {
+        let batch_offset = offset + (batch_index * BATCH_SIZE);
+        let transaction_keys: Vec<(usize, RistrettoPoint)> = batch.iter().enumerate()
+            .filter_map(|(i, (recipient, _))| recipient.transaction_key.map(|key| (i, key)))
+            .collect();
+
+        //batch compression doubles the points: 2 * (a/2) * R = a * R
+        let points: Vec<RistrettoPoint> = transaction_keys.iter().map(|(_, key)| half_view * key).collect();
+        for ((i, _), point) in transaction_keys.iter().zip(RistrettoPoint::double_and_compress_batch(&points)) {
+            let shared_secret = SharedSecret::from_compressed(point);
+            if shared_secret.get_view_tag() == batch[*i].0.view_tag {
+                detected.push((batch_offset + i, shared_secret));
+            }
+        }
+
+        //outputs without a transaction key can only be scanned with a shared transaction key
+        if let Some(shared) = shared {
+            for (i, (recipient, _)) in batch.iter().enumerate() {
+                if recipient.transaction_key.is_some() {
+                    continue
+                }
+                let shared_secret = shared.with_output_index((batch_offset + i) as u64);
+                if shared_secret.get_view_tag() == recipient.view_tag {
+                    detected.push((batch_offset + i, shared_secret));
+                }
+            }
+        }
+    }
+    detected.sort_by_key(|(index, _)| *index);
+    return detected
+}
+
+///Check the public key and commitment of an output, and decrypt its content.
+///
+///`check_key` checks the one-time key offset of each derivation version.
+fn open<T>(
+    recipient: &Recipient, commitment: &Commitment, shared_secret: &SharedSecret, check_key: impl Fn(&Scalar) -> Option<T>
+) -> Option<(T, u64, Scalar, Option<PaymentId>)> {
+    let (checked, _, value, blinding) = shared_secret.open_enote(recipient, commitment, check_key)?;
+    let payment_id = recipient.encrypted_payment_id
+        .map(|encrypted| shared_secret.decrypt_payment_id(encrypted));
+    return Some((checked, value, blinding, payment_id))
+}
+
+
+///An output found by a `Scanner`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScannedOutput {
+    ///Index of the output in the scanned outputs
+    pub index: usize,
+    pub value: u64,
+    pub blinding: Scalar,
+    ///Subaddress coordinates, if scanned with subaddress keys
+    pub coordinates: Option<(u32, u32)>,
+    ///Payment ID, if the output was sent to an integrated address
+    pub payment_id: Option<PaymentId>
+
+} impl ToBytes<'_> for ScannedOutput {}
+
+///Batch output scanner, for finding the outputs which belong to view keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scanner {
+    threads: usize
+
+} impl Scanner {
+    ///Create a scanner which uses all available threads.
+    pub fn new() -> Self {
+        let threads = thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1);
+        return Self::with_threads(threads)
+    }
+
+    ///Create a scanner which uses (at most) `threads` threads.
+    pub fn with_threads(threads: usize) -> Self {
+        return Self{threads: threads.max(1)}
+    }
+
+    ///Calculate the shared secrets of all outputs on multiple threads,
+    ///and return the indices and shared secrets of the outputs with a matching view tag, in order.
+    fn detect(
+        &self, view: &Scalar, outputs: &[(Recipient, Commitment)], transaction_key: Option<RistrettoPoint>
+    ) -> Vec<(usize, SharedSecret)> {
+        let half_view = view * Scalar::from(2u64).invert();
+        let half_view = &half_view;
+        let shared = transaction_key.map(|key| SharedSecret::get(*view, &key));
+        let shared = shared.as_ref();
+        let chunk_size = outputs.len().div_ceil(self.threads).max(1);
+
+        return thread::scope(|scope| {
+            let handles: Vec<_> = outputs.chunks(chunk_size).enumerate()
+                .map(|(i, chunk)| scope.spawn(move || detect(half_view, i * chunk_size, chunk, shared)))
+                .collect();
+            handles.into_iter()
+                .flat_map(|handle| handle.join().expect("Scanner thread panicked"))
+                .collect()
+        })
+    }
+
+    ///Scan outputs for the ones which belong to CryptoNote keys.
+    ///
+    ///Returns the found outputs, in order.
+    pub fn scan_cryptonote(&self, keys: &CryptoNotePrivateView, outputs: &[(Recipient, Commitment)]) -> Vec<ScannedOutput> {
+        return self.scan_cryptonote_internal(keys, outputs, None)
+    }
+
+    ///Scan the outputs of one transaction, which has a transaction key shared between its outputs (see `send_multiple`),
+    ///for the ones which belong to CryptoNote keys.
+    ///
+    ///`outputs` must be all outputs of the transaction, in order,
+    ///since outputs without their own transaction key are received with their index (see `receive_with_index`).
+    ///
+    ///Returns the found outputs, in order.
+    pub fn scan_cryptonote_with_transaction_key(
+        &self, keys: &CryptoNotePrivateView, outputs: &[(Recipient, Commitment)], transaction_key: RistrettoPoint
+    ) -> Vec<ScannedOutput> {
+        return self.scan_cryptonote_internal(keys, outputs, Some(transaction_key))
+    }
+
+    ///Internal CryptoNote scanning functionality
+    fn scan_cryptonote_internal(
+        &self, keys: &CryptoNotePrivateView, outputs: &[(Recipient, Commitment)], transaction_key: Option<RistrettoPoint>
+    ) -> Vec<ScannedOutput> {
+        let mut scanned: Vec<ScannedOutput> = Vec::new();
+        for (index, shared_secret) in self.detect(&keys.view, outputs, transaction_key) {
+            let (recipient, commitment) = &outputs[index];
+
+            //check public key
+            let check_key = |offset: &Scalar| (keys.spend + (offset * G) == recipient.public_key).then_some(());
+            if let Some((_, value, blinding, payment_id)) = open(recipient, commitment, &shared_secret, check_key) {
+                scanned.push(ScannedOutput{index, value, blinding, coordinates: None, payment_id});
+            }
+        }
+        return scanned
+    }
+
+    ///Scan outputs for the ones which belong to subaddress keys.
+    ///
+    ///Payments to subaddresses always have their own transaction key, so a shared transaction key is not needed.
+    ///
+    ///If the keys have a lookahead window (see `MasterPrivateView::init_lookahead`),
+    ///it is moved past each found output, and the remaining outputs are checked again with the new subaddresses.
+    ///
+    ///Returns the found outputs, in order.
+    pub fn scan_subaddress(&self, keys: &mut MasterPrivateView, outputs: &[(Recipient, Commitment)]) -> Vec<ScannedOutput> {
+        let mut scanned: Vec<ScannedOutput> = Vec::new();
+        let mut pending = self.detect(&keys.view, outputs, None);
+        loop {
+            let mut remaining: Vec<(usize, SharedSecret)> = Vec::new();
+            let mut found = false;
+            for (index, shared_secret) in pending {
+                let (recipient, commitment) = &outputs[index];
+
+                //check public key: D' = P - H(aR)G
+                let table = match keys.get_table() {
+                    Ok(table) => table,
+                    Err(_) => return scanned
+                };
+                let found_spend = DerivationVersion::ALL.iter().any(|version|
+                    table.coords.contains_key(&(recipient.public_key - (&shared_secret.key_offset(*version) * G)).compress()));
+                if !found_spend {
+                    remaining.push((index, shared_secret));
+                    continue
+                }
+                found = true;
+
+                let check_key = |offset: &Scalar| table.coords.get(&(recipient.public_key - (offset * G)).compress()).copied();
+                if let Some((coordinates, value, blinding, payment_id)) = open(recipient, commitment, &shared_secret, check_key) {
+                    keys.extend_lookahead(coordinates);
+                    scanned.push(ScannedOutput{index, value, blinding, coordinates: Some(coordinates), payment_id});
+                }
+            }
+
+            //new subaddresses may have been initialized
+            if !found || remaining.is_empty() {
+                break
+            }
+            pending = remaining;
+        }
+        scanned.sort_by_key(|output| output.index);
+        return scanned
+    }
+
+} impl Default for Scanner {
+    fn default() -> Self {
+        return Self::new()
+    }
+}
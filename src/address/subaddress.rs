@@ -8,6 +8,16 @@
 //!
 //! **By default, this protocol is vulnerable to the
 //! [Janus attack](https://web.getmonero.org/2019/10/18/subaddress-janus.html).**
+//! To protect against it, send with `SubaddressPublic::send_hardened`
+//! and receive with `MasterPrivateKeys::receive_hardened`.
+//! This includes an auxiliary transaction key `r * G` along with the transaction key `r * D`,
+//! and the receiver checks that the transaction key uses the same subaddress as the one it received the payment to.
+//! Note that this check requires the private spend key, so view-only wallets can not perform it.
+//!
+//! Instead of initializing a fixed number of coordinates with `init`,
+//! wallets can use a lookahead window with `init_lookahead`:
+//! whenever a payment to a subaddress is found with one of the `*_and_extend` methods (or a `Scanner`),
+//! the lookup table is extended past it, so that a wallet restored from a seed finds every used subaddress.
 
 use std::collections::HashMap;
 use zeroize::Zeroize;
@@ -15,7 +25,8 @@ use zeroize::Zeroize;
 use crate::internal_common::*;
 use super::{
     ecdh::*,
-    Recipient
+    Recipient,
+    outgoing::OutgoingViewKey
 };
 
 
@@ -62,6 +73,47 @@ pub(crate) type LookupTable = GenericLookupTable<Scalar>;
 pub(crate) type LookupTableView = GenericLookupTable<RistrettoPoint>;
 
 
+///Lookahead window for the lookup table, see `MasterPrivateKeys::init_lookahead`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Zeroize)]
+pub(crate) struct Lookahead {
+    ///Number of accounts to initialize past the highest used account
+    accounts: u32,
+    ///Number of subaddresses to initialize past the highest used subaddress, per account
+    subaddresses: u32,
+    ///Number of initialized subaddresses, for each initialized account
+    sizes: Vec<u32>
+
+} impl Lookahead {
+    ///Create a new lookahead window, returning the coordinates to initialize.
+    pub(crate) fn new(accounts: u32, subaddresses: u32) -> (Self, Vec<(u32, u32)>) {
+        let mut lookahead = Self{accounts, subaddresses, sizes: Vec::new()};
+        let coordinates = lookahead.extend((0, 0));
+        return (lookahead, coordinates)
+    }
+
+    ///Extend the window past used coordinates, returning the coordinates to initialize.
+    pub(crate) fn extend(&mut self, used: (u32, u32)) -> Vec<(u32, u32)> {
+        let mut coordinates: Vec<(u32, u32)> = Vec::new();
+
+        //new accounts
+        let accounts = used.0.saturating_add(self.accounts);
+        while (self.sizes.len() as u32) < accounts {
+            let x = self.sizes.len() as u32;
+            coordinates.extend((0..self.subaddresses).map(|y| (x, y)));
+            self.sizes.push(self.subaddresses);
+        }
+
+        //new subaddresses in the used account
+        if let Some(size) = self.sizes.get_mut(used.0 as usize) {
+            let subaddresses = used.1.saturating_add(self.subaddresses);
+            coordinates.extend((*size..subaddresses).map(|y| (used.0, y)));
+            *size = (*size).max(subaddresses);
+        }
+        return coordinates
+    }
+}
+
+
 trait LookupTableProtocol<S: Zeroize> {
     fn get_subkey_unchecked() -> Result<S, SubaddressError>;
 }
@@ -80,7 +132,8 @@ pub struct MasterPrivateKeys {
     pub view: Scalar,
     pub spend: Scalar,
 
-    table: Option<LookupTable>
+    table: Option<LookupTable>,
+    lookahead: Option<Lookahead>
 
 } impl MasterPrivateKeys {
     ///Get private spend key for subaddress without checking if the coordinates are initialized.
@@ -142,10 +195,16 @@ pub struct MasterPrivateKeys {
         return MasterPrivateView {
             view: self.view.to_owned(),
             spend: self.spend.to_public(),
-            table: None
+            table: None,
+            lookahead: None
         }
     }
 
+    ///Derive the outgoing view key of these keys, for recovering sent payments.
+    pub fn outgoing_view_key(&self) -> OutgoingViewKey {
+        return OutgoingViewKey::from_spend(&self.spend)
+    }
+
     ///Initialize all coordinates on the table up to these `x` and `y` values, exclusive.
     ///
     ///This may take a lot of time and memory when using large `x` and `y` values,
@@ -163,6 +222,34 @@ pub struct MasterPrivateKeys {
         }
     }
 
+    ///Initialize the lookup table with a lookahead window of `accounts` accounts,
+    ///each with `subaddresses` subaddresses.
+    ///
+    ///Whenever a payment to a subaddress is found with `receive_and_extend` or `recover_coordinates_and_extend`,
+    ///the window is moved past it, initializing the next `accounts` accounts
+    ///and the next `subaddresses` subaddresses of its account.
+    ///
+    ///Note that the window is not included in `to_bytes`, and must be initialized again after `from_bytes`.
+    pub fn init_lookahead(&mut self, accounts: u32, subaddresses: u32) {
+        let (lookahead, coordinates) = Lookahead::new(accounts, subaddresses);
+        self.lookahead = Some(lookahead);
+        self.get_mut_table_else_new().reserve(coordinates.len());
+        for item in coordinates {
+            self.init_coordinates(item);
+        }
+    }
+
+    ///Move the lookahead window past used coordinates, if there is one.
+    pub(crate) fn extend_lookahead(&mut self, coordinates: (u32, u32)) {
+        let new_coordinates = match self.lookahead.as_mut() {
+            Some(lookahead) => lookahead.extend(coordinates),
+            None => return
+        };
+        for item in new_coordinates {
+            self.init_coordinates(item);
+        }
+    }
+
     ///Initialize coordinates in the lookup table.
     pub fn init_coordinates(&mut self, coordinates: (u32, u32)) -> () {
         let keypair = Self::from_keys(self.view.clone(), self.spend.clone());
@@ -200,22 +287,44 @@ pub struct MasterPrivateKeys {
     ///If the private key cannot be found, returns `Err(SubaddressError)`.
     pub fn recover_coordinates(&self, public_key: RistrettoPoint, shared_secret: SharedSecret) -> Result<(u32, u32), SubaddressError> {
         let table = self.get_table()?;
-        //D' = P - H(aR)G
-        return match table.coords.get(&(public_key - (&shared_secret.as_scalar() * G)).compress()) {
+        //D' = P - H(aR)G, for each derivation version
+        return match DerivationVersion::ALL.iter()
+            .find_map(|version| table.coords.get(&(public_key - (&shared_secret.key_offset(*version) * G)).compress())) {
             Some(coords) => Ok(*coords),
             None => Err(SubaddressError::KeyNotFound)
         }
     }
 
+    ///Same as `recover_coordinates`, except that if there is a lookahead window (see `init_lookahead`),
+    ///it is moved past the found coordinates.
+    pub fn recover_coordinates_and_extend(&mut self, public_key: RistrettoPoint, shared_secret: SharedSecret) -> Result<(u32, u32), SubaddressError> {
+        let coordinates = self.recover_coordinates(public_key, shared_secret)?;
+        self.extend_lookahead(coordinates);
+        return Ok(coordinates)
+    }
+
     ///Given a shared secret, and subaddress coordinates, deterministically derive a unique ephemeral private key.
     ///**These keys should never be reused.**
     ///
     ///If the coordinates are not initialized, return `Err(SubaddressError)`.
+    ///
+    ///This uses the legacy derivation, see `derive_key_with_version` for new enotes.
     pub fn derive_key(&self, shared_secret: SharedSecret, coordinates: (u32, u32)) -> Result<Scalar, SubaddressError> {
+        return self.derive_key_with_version(shared_secret, coordinates, DerivationVersion::Legacy)
+    }
+
+    ///Given a shared secret, subaddress coordinates, and derivation version,
+    ///deterministically derive a unique ephemeral private key.
+    ///**These keys should never be reused.**
+    ///
+    ///If the coordinates are not initialized, return `Err(SubaddressError)`.
+    pub fn derive_key_with_version(
+        &self, shared_secret: SharedSecret, coordinates: (u32, u32), version: DerivationVersion
+    ) -> Result<Scalar, SubaddressError> {
         let table = self.get_table()?;
         //p = H(aR) + b + H(a,x,y)
         return match table.secrets.get(&coordinates) {
-            Some(key) => Ok(key + shared_secret.as_scalar()),
+            Some(key) => Ok(key + shared_secret.key_offset(version)),
             None => Err(SubaddressError::KeyNotFound)
         }
     }
@@ -231,7 +340,7 @@ pub struct MasterPrivateKeys {
 
     ///Import from private keys.
     pub fn from_keys(private_view_key: Scalar, private_spend_key: Scalar) -> Self {
-        return Self{view: private_view_key, spend: private_spend_key, table: None}
+        return Self{view: private_view_key, spend: private_spend_key, table: None, lookahead: None}
     }
 
     ///Deterministically convert a seed into a Subaddress private key.
@@ -249,9 +358,42 @@ pub struct MasterPrivateKeys {
     ///
     ///Returns `Some(EnoteKeys)` if the enote belongs to these keys, or `None` if not.
     pub fn receive(&self, recipient: &Recipient, commitment: &Commitment) -> Option<EnoteKeys> {
+        return self.receive_internal(recipient, commitment, false).map(|(_, keys)| keys)
+    }
+
+    ///Same as `receive`, except that if there is a lookahead window (see `init_lookahead`),
+    ///it is moved past the subaddress the payment was received to.
+    pub fn receive_and_extend(&mut self, recipient: &Recipient, commitment: &Commitment) -> Option<EnoteKeys> {
+        let (coordinates, keys) = self.receive_internal(recipient, commitment, false)?;
+        self.extend_lookahead(coordinates);
+        return Some(keys)
+    }
+
+    ///"Receive" a payment sent with `SubaddressPublic::send_hardened`, decrypting its content, given the pedersen commitment.
+    ///
+    ///This also checks the auxiliary transaction key, protecting against the Janus attack.
+    ///
+    ///**Make sure that the appropiate coordinates are initialized first!**
+    ///Otherwise the payment won't be recognized.
+    ///
+    ///Returns `Some(EnoteKeys)` if the enote belongs to these keys and passes the check, or `None` if not.
+    pub fn receive_hardened(&self, recipient: &Recipient, commitment: &Commitment) -> Option<EnoteKeys> {
+        return self.receive_internal(recipient, commitment, true).map(|(_, keys)| keys)
+    }
+
+    ///Same as `receive_hardened`, except that if there is a lookahead window (see `init_lookahead`),
+    ///it is moved past the subaddress the payment was received to.
+    pub fn receive_hardened_and_extend(&mut self, recipient: &Recipient, commitment: &Commitment) -> Option<EnoteKeys> {
+        let (coordinates, keys) = self.receive_internal(recipient, commitment, true)?;
+        self.extend_lookahead(coordinates);
+        return Some(keys)
+    }
+
+    ///Internal receiving functionality, returning the coordinates of the subaddress and the enote's private keys
+    fn receive_internal(&self, recipient: &Recipient, commitment: &Commitment, hardened: bool) -> Option<((u32, u32), EnoteKeys)> {
         fn receive_inner(
-            master_keys: &MasterPrivateKeys, recipient: &Recipient, commitment: &Commitment
-        ) -> Result<EnoteKeys, SubaddressError> {
+            master_keys: &MasterPrivateKeys, recipient: &Recipient, commitment: &Commitment, hardened: bool
+        ) -> Result<((u32, u32), EnoteKeys), SubaddressError> {
             //check view tag
             let transaction_key = match recipient.transaction_key {
                 Some(key) => key,
@@ -262,24 +404,31 @@ pub struct MasterPrivateKeys {
                 return Err(SubaddressError::Unspecified("".to_string()))
             }
 
-            //check public key
-            let coordinates = master_keys.recover_coordinates(recipient.public_key, shared_secret.clone())?;
-            let owner = master_keys.derive_key(shared_secret.clone(), coordinates)?;
-
-            //check commitment
-            let value = shared_secret.decrypt_amount(recipient.encrypted_amount);
-            let blinding = shared_secret.as_scalar();
-            if Commitment::commit(value, blinding) != *commitment {
-                return Err(SubaddressError::Unspecified("".to_string()))
+            //check public key and commitment: D' = P - H(aR)G
+            let table = master_keys.get_table()?;
+            let ((coordinates, owner), _, value, blinding) = shared_secret.open_enote(recipient, commitment, |offset| {
+                let coordinates = *table.coords.get(&(recipient.public_key - (offset * G)).compress())?;
+                return Some((coordinates, table.secrets.get(&coordinates)? + offset))
+            }).ok_or(SubaddressError::KeyNotFound)?;
+
+            //check that the transaction key is for the same subaddress: R == (b + H(a,x,y)) * R_aux
+            if hardened {
+                let auxiliary_key = match recipient.auxiliary_key {
+                    Some(key) => key,
+                    None => return Err(SubaddressError::Unspecified("".to_string()))
+                };
+                if master_keys.get_subaddress_key(coordinates)? * auxiliary_key != transaction_key {
+                    return Err(SubaddressError::Unspecified("".to_string()))
+                }
             }
 
-            return Ok(EnoteKeys{
+            return Ok((coordinates, EnoteKeys{
                 owner,
                 value,
                 blinding
-            })
+            }))
         }
-        if let Ok(keys) = receive_inner(self, recipient, commitment) {
+        if let Ok(keys) = receive_inner(self, recipient, commitment, hardened) {
             return Some(keys)
         }
         return None
@@ -392,7 +541,8 @@ pub struct MasterPrivateView {
     pub view: Scalar,
     pub spend: RistrettoPoint,
 
-    table: Option<LookupTableView>
+    table: Option<LookupTableView>,
+    lookahead: Option<Lookahead>
 
 } impl MasterPrivateView {
     ///Get public spend key for subaddress without checking if the coordinates are initialized.
@@ -466,6 +616,34 @@ pub struct MasterPrivateView {
         }
     }
 
+    ///Initialize the lookup table with a lookahead window of `accounts` accounts,
+    ///each with `subaddresses` subaddresses.
+    ///
+    ///Whenever a payment to a subaddress is found with `receive_and_extend` or `recover_coordinates_and_extend`,
+    ///the window is moved past it, initializing the next `accounts` accounts
+    ///and the next `subaddresses` subaddresses of its account.
+    ///
+    ///Note that the window is not included in `to_bytes`, and must be initialized again after `from_bytes`.
+    pub fn init_lookahead(&mut self, accounts: u32, subaddresses: u32) {
+        let (lookahead, coordinates) = Lookahead::new(accounts, subaddresses);
+        self.lookahead = Some(lookahead);
+        self.get_mut_table_else_new().reserve(coordinates.len());
+        for item in coordinates {
+            self.init_coordinates(item);
+        }
+    }
+
+    ///Move the lookahead window past used coordinates, if there is one.
+    pub(crate) fn extend_lookahead(&mut self, coordinates: (u32, u32)) {
+        let new_coordinates = match self.lookahead.as_mut() {
+            Some(lookahead) => lookahead.extend(coordinates),
+            None => return
+        };
+        for item in new_coordinates {
+            self.init_coordinates(item);
+        }
+    }
+
     ///Initialize coordinates in the lookup table.
     pub fn init_coordinates(&mut self, coordinates: (u32, u32)) -> () {
         let keypair = Self::from_keys(self.view.clone(), self.spend.clone());
@@ -503,29 +681,51 @@ pub struct MasterPrivateView {
     ///If the private key cannot be found, returns `Err(SubaddressError)`.
     pub fn recover_coordinates(&self, public_key: RistrettoPoint, shared_secret: SharedSecret) -> Result<(u32, u32), SubaddressError> {
         let table = self.get_table()?;
-        //D' = P - H(aR)G
-        return match table.coords.get(&(public_key - (&shared_secret.as_scalar() * G)).compress()) {
+        //D' = P - H(aR)G, for each derivation version
+        return match DerivationVersion::ALL.iter()
+            .find_map(|version| table.coords.get(&(public_key - (&shared_secret.key_offset(*version) * G)).compress())) {
             Some(coords) => Ok(*coords),
             None => Err(SubaddressError::KeyNotFound)
         }
     }
 
-    ///Given a shared secret, and subaddress coordinates, deterministically derive a unique ephemeral private key.
+    ///Same as `recover_coordinates`, except that if there is a lookahead window (see `init_lookahead`),
+    ///it is moved past the found coordinates.
+    pub fn recover_coordinates_and_extend(&mut self, public_key: RistrettoPoint, shared_secret: SharedSecret) -> Result<(u32, u32), SubaddressError> {
+        let coordinates = self.recover_coordinates(public_key, shared_secret)?;
+        self.extend_lookahead(coordinates);
+        return Ok(coordinates)
+    }
+
+    ///Given a shared secret, and subaddress coordinates, deterministically derive a unique ephemeral public key.
     ///**These keys should never be reused.**
     ///
     ///If the coordinates are not initialized, return `Err(SubaddressError)`.
+    ///
+    ///This uses the legacy derivation, see `derive_key_with_version` for new enotes.
     pub fn derive_key(&self, shared_secret: SharedSecret, coordinates: (u32, u32)) -> Result<RistrettoPoint, SubaddressError> {
+        return self.derive_key_with_version(shared_secret, coordinates, DerivationVersion::Legacy)
+    }
+
+    ///Given a shared secret, subaddress coordinates, and derivation version,
+    ///deterministically derive a unique ephemeral public key.
+    ///**These keys should never be reused.**
+    ///
+    ///If the coordinates are not initialized, return `Err(SubaddressError)`.
+    pub fn derive_key_with_version(
+        &self, shared_secret: SharedSecret, coordinates: (u32, u32), version: DerivationVersion
+    ) -> Result<RistrettoPoint, SubaddressError> {
         let table = self.get_table()?;
         //p = H(aR) + b + H(a,x,y)
         return match table.secrets.get(&coordinates) {
-            Some(key) => Ok(key + (&shared_secret.as_scalar() * G)),
+            Some(key) => Ok(key + (&shared_secret.key_offset(version) * G)),
             None => Err(SubaddressError::KeyNotFound)
         }
     }
 
     ///Import from a private view key and a public spend key.
     pub fn from_keys(private_view_key: Scalar, public_spend_key: RistrettoPoint) -> Self {
-        return Self{view: private_view_key, spend: public_spend_key, table: None}
+        return Self{view: private_view_key, spend: public_spend_key, table: None, lookahead: None}
     }
 
     ///"Receive" a payment, decrypting its content, given the pedersen commitment.
@@ -535,9 +735,22 @@ pub struct MasterPrivateView {
     ///
     ///Returns the amount and blinding factor of the pedersen commitment if the enote belongs to these keys, or `None` if not.
     pub fn receive(&self, recipient: &Recipient, commitment: &Commitment) -> Option<(u64, Scalar)> {
+        return self.receive_internal(recipient, commitment).map(|(_, value, blinding)| (value, blinding))
+    }
+
+    ///Same as `receive`, except that if there is a lookahead window (see `init_lookahead`),
+    ///it is moved past the subaddress the payment was received to.
+    pub fn receive_and_extend(&mut self, recipient: &Recipient, commitment: &Commitment) -> Option<(u64, Scalar)> {
+        let (coordinates, value, blinding) = self.receive_internal(recipient, commitment)?;
+        self.extend_lookahead(coordinates);
+        return Some((value, blinding))
+    }
+
+    ///Internal receiving functionality, returning the coordinates of the subaddress, and the amount and blinding factor
+    fn receive_internal(&self, recipient: &Recipient, commitment: &Commitment) -> Option<((u32, u32), u64, Scalar)> {
         fn receive_inner(
             master_keys: &MasterPrivateView, recipient: &Recipient, commitment: &Commitment
-        ) -> Result<(u64, Scalar), SubaddressError> {
+        ) -> Result<((u32, u32), u64, Scalar), SubaddressError> {
             //check view tag
             let transaction_key = match recipient.transaction_key {
                 Some(key) => key,
@@ -548,17 +761,13 @@ pub struct MasterPrivateView {
                 return Err(SubaddressError::Unspecified("".to_string()))
             }
 
-            //check public key
-            master_keys.recover_coordinates(recipient.public_key, shared_secret.clone())?;
-
-            //check commitment
-            let value = shared_secret.decrypt_amount(recipient.encrypted_amount);
-            let blinding = shared_secret.as_scalar();
-            if Commitment::commit(value, blinding) != *commitment {
-                return Err(SubaddressError::Unspecified("".to_string()))
-            }
+            //check public key and commitment: D' = P - H(aR)G
+            let table = master_keys.get_table()?;
+            let (coordinates, _, value, blinding) = shared_secret.open_enote(recipient, commitment, |offset|
+                table.coords.get(&(recipient.public_key - (offset * G)).compress()).copied()
+            ).ok_or(SubaddressError::KeyNotFound)?;
 
-            return Ok((value, blinding))
+            return Ok((coordinates, value, blinding))
         }
         if let Ok(keys) = receive_inner(self, recipient, commitment) {
             return Some(keys)
@@ -641,6 +850,7 @@ impl Zeroize for MasterPrivateView {
     fn zeroize(&mut self) {
         self.view.zeroize();
         self.table.zeroize();
+        self.lookahead.zeroize();
     }
 
 } impl Drop for MasterPrivateView {
@@ -685,8 +895,16 @@ pub struct SubaddressPublic {
 
     ///Derive the unique ephemeral public key given a shared secret.
     ///**These keys should never be reused.**
+    ///
+    ///This uses the legacy derivation, see `derive_key_with_version` for new enotes.
     pub fn derive_key(&self, shared_secret: SharedSecret) -> RistrettoPoint {
-        return self.spend + (&shared_secret.as_scalar() * G)
+        return self.derive_key_with_version(shared_secret, DerivationVersion::Legacy)
+    }
+
+    ///Derive the unique ephemeral public key given a shared secret and derivation version.
+    ///**These keys should never be reused.**
+    pub fn derive_key_with_version(&self, shared_secret: SharedSecret, version: DerivationVersion) -> RistrettoPoint {
+        return self.spend + (&shared_secret.key_offset(version) * G)
     }
 
     ///"Send" to this address, where only the recipient can detect that the payment is for them.
@@ -696,20 +914,72 @@ pub struct SubaddressPublic {
     ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
     ///and the public data for the receiver to detect the payment.
     pub fn send(&self, amount: u64) -> (Scalar, Recipient) {
+        return self.send_internal(amount, self.generate_transaction_key(amount), false)
+    }
+
+    ///"Send" to this address, given a transaction/ECDH private key,
+    ///where only the recipient can detect that the payment is for them.
+    ///
+    ///The transaction private key should be kept if the payment needs to be proven later (see `PaymentProof`).
+    ///**The transaction key should never be reused.**
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send_with_key(&self, amount: u64, transaction_key: Scalar) -> (Scalar, Recipient) {
+        return self.send_internal(amount, transaction_key, false)
+    }
+
+    ///"Send" to this address, including an auxiliary transaction key which protects against the Janus attack.
+    ///
+    ///Note that `MasterPrivateKeys::receive_hardened` should be used to receive payments created by this method.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send_hardened(&self, amount: u64) -> (Scalar, Recipient) {
+        return self.send_internal(amount, self.generate_transaction_key(amount), true)
+    }
+
+    ///"Send" to this address, where only the recipient can detect that the payment is for them,
+    ///and only the sender can recover the payment later, using their outgoing view key.
+    ///
+    ///The transaction/ECDH key is derived from the outgoing view key, see `OutgoingViewKey::recover`.
+    ///
+    ///Returns the blinding factor of the pedersen commitment (for use in a rangeproof),
+    ///and the public data for the receiver to detect the payment.
+    pub fn send_with_outgoing(&self, amount: u64, outgoing: &OutgoingViewKey) -> (Scalar, Recipient) {
+        let (nonce, transaction_sk) = outgoing.generate_transaction_key();
+        let (blinding, mut recipient) = self.send_internal(amount, transaction_sk, false);
+        outgoing.encrypt_destination(nonce, &self.view, &mut recipient);
+        return (blinding, recipient)
+    }
+
+    ///Generate a random transaction key.
+    fn generate_transaction_key(&self, amount: u64) -> Scalar {
         let seed = batch_encode_points(&vec!(self.view, self.spend)).concat();
         let seed = h_scalar(&[seed, amount.to_le_bytes().to_vec()].concat());
-        let transaction_sk = seed + Scalar::generate();
+        return seed + Scalar::generate()
+    }
 
+    ///Internal sending functionality
+    fn send_internal(&self, amount: u64, transaction_sk: Scalar, hardened: bool) -> (Scalar, Recipient) {
         let (shared_secret, transaction_key) = self.shared_secret(transaction_sk);
         let view_tag = shared_secret.get_view_tag();
-        let encrypted_amount = shared_secret.encrypt_amount(amount);
-        let blinding = shared_secret.as_scalar();
+        let encrypted_amount = shared_secret.encrypt_amount_with_version(amount, DerivationVersion::CURRENT);
+        let blinding = shared_secret.blinding(DerivationVersion::CURRENT);
+        //R_aux = r * G
+        let auxiliary_key = match hardened {
+            true => Some(transaction_sk.to_public()),
+            false => None
+        };
 
         let recipient = Recipient {
-            public_key: self.derive_key(shared_secret),
+            public_key: self.derive_key_with_version(shared_secret, DerivationVersion::CURRENT),
             transaction_key: Some(transaction_key),
             view_tag,
-            encrypted_amount
+            encrypted_amount,
+            encrypted_payment_id: None,
+            auxiliary_key,
+            encrypted_destination: None
         };
         return (blinding, recipient)
     }
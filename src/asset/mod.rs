@@ -0,0 +1,85 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Confidential assets: multiple asset types on the same RingCT ledger.
+//!
+//! Each asset has its own generator `H_a`, derived by hashing its `AssetId`.
+//! To hide which asset an output holds, its generator is blinded into an `AssetTag`: `T = H_a + (r * G)`.
+//! Commitments are then made using the asset tag instead of `H`: `(v * T) + (b * G)`.
+//!
+//! A `SurjectionProof` shows that an output's asset tag has the same asset as one of the input tags,
+//! without revealing which one.
+//! Outputs should also have a rangeproof, see `BulletPlusRangeProof::prove_with_asset_tag`.
+//!
+//! Note that the blinding factor of an asset commitment includes `v * r`,
+//! which must be taken into account when balancing inputs and outputs.
+
+mod surjection;
+
+pub use surjection::SurjectionProof;
+
+use crate::internal_common::*;
+
+
+///Identifies an asset type.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct AssetId(
+    pub [u8; 32]
+
+); impl AssetId {
+    ///Derive an asset ID from a name, or any other unique data.
+    pub fn from_name(name: &[u8]) -> Self {
+        return Self(domain_h_bytes(name, domains::ASSET_ID))
+    }
+
+    ///Return this asset's (unblinded) generator point, `H_a`.
+    pub fn generator(&self) -> RistrettoPoint {
+        return domain_h_point(&self.0, domains::ASSET_GENERATOR)
+    }
+
+} impl ToBytes<'_> for AssetId {}
+
+///A blinded asset generator: `T = H_a + (r * G)`
+///
+///Used in place of `H` when committing to the value of an asset.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AssetTag(
+    pub RistrettoPoint
+
+); impl AssetTag {
+    ///Create an asset tag for `asset` with blinding factor `blinding`.
+    pub fn new(asset: &AssetId, blinding: Scalar) -> Self {
+        return Self(asset.generator() + (&blinding * G))
+    }
+
+    ///Create an unblinded asset tag, for example when the asset is public (such as fees or issuance).
+    pub fn unblinded(asset: &AssetId) -> Self {
+        return Self(asset.generator())
+    }
+
+    ///Create a commitment to `value` of this asset, with blinding factor `blinding`.
+    ///
+    ///`(v * T) + (b * G)`
+    pub fn commit(&self, value: u64, blinding: Scalar) -> Commitment {
+        return Commitment(RistrettoPoint::multiscalar_mul(
+            vec!(Scalar::from(value), blinding), vec!(self.0, G_POINT)
+        ))
+    }
+
+    ///Return the elliptic curve point which represents this asset tag.
+    pub fn to_point(&self) -> RistrettoPoint {
+        return self.0
+    }
+
+} impl ToBytes<'_> for AssetTag {
+    fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        return self.0.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        return Ok(Self(RistrettoPoint::from_bytes(bytes)?))
+    }
+}
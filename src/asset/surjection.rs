@@ -0,0 +1,125 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use zeroize::Zeroize;
+
+use crate::internal_common::*;
+use super::AssetTag;
+
+///Create the signed message, including a hash of all input tags and the output tag.
+fn create_message(input_tags: &[AssetTag], output_tag: &AssetTag, msg: &[u8]) -> [u8; 32] {
+    let points: Vec<RistrettoPoint> = input_tags.iter().chain([output_tag])
+        .map(|tag| tag.0).collect();
+    return h_bytes(&[msg, &batch_encode_points(&points).concat()].concat())
+}
+
+///Return the ring of public keys: the output tag minus each input tag.
+///
+///The output's key is `r_out - r_in` for the input with the same asset.
+fn get_keys(input_tags: &[AssetTag], output_tag: &AssetTag) -> Vec<RistrettoPoint> {
+    return input_tags.iter().map(|tag| output_tag.0 - tag.0).collect()
+}
+
+
+///An asset surjection proof.
+///
+///Proves that an output's asset tag has the same asset as one of the input tags,
+///without revealing which one.
+///This is a ring signature over the differences between the output tag and each input tag.
+///
+///The proof signs a message, which should be the transaction it belongs to (such as its prefix hash),
+///so that it can't be reused in another transaction with the same tags.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurjectionProof {
+    c_0: Scalar,
+    s: Vec<Scalar>
+
+} impl SurjectionProof {
+    ///Create a surjection proof, given the input tags, the index and blinding factor of the input tag with the same asset,
+    ///the output tag and its blinding factor, and a message.
+    ///
+    ///Returns a surjection proof if proving was successful,
+    ///or `SignatureError::AssetMismatch` if the output does not have the same asset as the chosen input.
+    pub fn prove(
+        input_tags: &[AssetTag], index: usize, input_blinding: Scalar, output_tag: &AssetTag, output_blinding: Scalar, msg: &[u8]
+    ) -> Result<Self, SignatureError> {
+        let n = input_tags.len();
+        if index >= n {
+            return Err(SignatureError::Malformed)
+        }
+
+        let keys = get_keys(input_tags, output_tag);
+        let mut key = output_blinding - input_blinding;
+        if keys[index] != &key * G {
+            return Err(SignatureError::AssetMismatch)
+        }
+
+        let m = create_message(input_tags, output_tag, msg);
+        let m = m.as_slice();
+
+        let mut s: Vec<Scalar> = (0..n).map(|_| Scalar::generate()).collect();
+
+        //compute starting value
+        let mut left = &s[index] * G;
+
+        let mut c_i = Scalar::one();
+        let mut c_0 = c_i;
+        let mut i = index;
+        for _ in 0..n {
+            i = (i + 1) % n;
+
+            c_i = domain_h_scalar(&[
+                m, &encode_point(&left)
+            ].concat(), domains::ASSET_SURJECTION);
+
+            if i == 0 { c_0 = c_i }
+            if i == index { break }
+
+            left = (&s[i] * G) + (c_i * keys[i]);
+        }
+        s[index] -= c_i * key;
+
+        key.zeroize();
+
+        return Ok(Self{c_0, s})
+    }
+
+    ///Given a surjection proof, the input tags, the output tag, and a message, check if it's valid.
+    ///
+    ///Returns `Ok()` if the proof is valid,
+    ///or `Err(SignatureError)` if it's invalid.
+    pub fn verify(
+        input_tags: &[AssetTag], output_tag: &AssetTag, msg: &[u8], proof: &SurjectionProof
+    ) -> Result<(), SignatureError> {
+        let n = input_tags.len();
+        if n == 0 || proof.s.len() != n {
+            return Err(SignatureError::Malformed)
+        }
+
+        let keys = get_keys(input_tags, output_tag);
+        let m = create_message(input_tags, output_tag, msg);
+        let m = m.as_slice();
+
+        //travel around the ring
+        let mut c_i = proof.c_0;
+        for (s_i, key) in proof.s.iter().zip(keys) {
+            //(s[i] * G) + (c[i] * keys[i])
+            let left = G_MULTISCALAR_MUL.vartime_mixed_multiscalar_mul(
+                vec!(s_i), vec!(c_i), vec!(key)
+            );
+
+            c_i = domain_h_scalar(&[
+                m, &encode_point(&left)
+            ].concat(), domains::ASSET_SURJECTION);
+        }
+        //check if we end up back where we started
+        return match c_i == proof.c_0 {
+            true => Ok(()),
+            false => Err(SignatureError::Invalid)
+        };
+    }
+
+} impl ToBytes<'_> for SurjectionProof {}
@@ -68,6 +68,8 @@ pub enum SignatureError {
     EnoteNotInRing,
     ///The ring is required to be sorted, but it is not
     UnsortedRing,
+    ///The output's asset does not match the asset of the chosen input.
+    AssetMismatch,
     ///Miscellaneous/unspecified error.
     Unspecified(String)
 
@@ -78,6 +80,7 @@ pub enum SignatureError {
             Self::Malformed => "Malformed signature or parameters.",
             Self::EnoteNotInRing => "Enote is not in ring.",
             Self::UnsortedRing => "The ring is not sorted.",
+            Self::AssetMismatch => "The output's asset does not match the input's asset.",
             Self::Unspecified(msg) => msg,
         })
     }
@@ -106,4 +109,122 @@ pub enum SubaddressError {
         })
     }
 
-} impl Error for SubaddressError {}
\ No newline at end of file
+} impl Error for SubaddressError {}
+
+///Address encoding errors
+#[derive(Debug, Clone)]
+pub enum AddressError {
+    ///The encoded address contains a character which is not valid base58.
+    InvalidCharacter,
+    ///The encoded address has the wrong length.
+    InvalidLength,
+    ///The checksum does not match, usually due to a typo.
+    InvalidChecksum,
+    ///The address is for a different network.
+    WrongNetwork,
+    ///The address type is unknown.
+    UnknownType,
+    ///A public key in the address is not a valid point.
+    InvalidKey,
+    ///Miscellaneous/unspecified error.
+    Unspecified(String)
+
+} impl Display for AddressError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self{
+            Self::InvalidCharacter => "Invalid character in address.",
+            Self::InvalidLength => "Invalid address length.",
+            Self::InvalidChecksum => "Invalid address checksum.",
+            Self::WrongNetwork => "The address is for a different network.",
+            Self::UnknownType => "Unknown address type.",
+            Self::InvalidKey => "Invalid public key in address.",
+            Self::Unspecified(msg) => msg,
+        })
+    }
+
+} impl Error for AddressError {}
+
+///Mnemonic seed phrase errors
+#[derive(Debug, Clone)]
+pub enum MnemonicError {
+    ///The seed phrase has the wrong number of words.
+    InvalidWordCount,
+    ///The word at the given index is not in the wordlist.
+    UnknownWord(usize),
+    ///The checksum does not match, usually due to a typo or swapped words.
+    InvalidChecksum
+
+} impl Display for MnemonicError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self{
+            Self::InvalidWordCount => "Invalid number of words.".to_string(),
+            Self::UnknownWord(index) => format!("Word {} is not in the wordlist.", index),
+            Self::InvalidChecksum => "Invalid seed phrase checksum.".to_string()
+        })
+    }
+
+} impl Error for MnemonicError {}
+
+///Transaction errors
+#[derive(Debug, Clone)]
+pub enum TransactionError {
+    ///The transaction has no inputs or no outputs.
+    Empty,
+    ///The inputs are not balanced with the outputs and fee.
+    Unbalanced,
+    ///The input at the given index is invalid, or could not be signed.
+    Input(usize, SignatureError),
+    ///The key image of the input at the given index is already used by an earlier input.
+    DuplicateKeyImage(usize),
+    ///The public key of the output at the given index is already used by an earlier output.
+    DuplicateOutputKey(usize),
+    ///The key image of the input at the given index has already been spent.
+    KeyImageSpent(usize),
+    ///The rangeproof is invalid, or could not be created.
+    RangeProof(RangeProofError),
+    ///Miscellaneous/unspecified error.
+    Unspecified(String)
+
+} impl Display for TransactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self{
+            Self::Empty => "The transaction has no inputs or no outputs.".to_string(),
+            Self::Unbalanced => "The transaction is not balanced.".to_string(),
+            Self::Input(index, e) => format!("Input {}: {}", index, e),
+            Self::DuplicateKeyImage(index) => format!("Input {}: Duplicate key image.", index),
+            Self::DuplicateOutputKey(index) => format!("Output {}: Duplicate public key.", index),
+            Self::KeyImageSpent(index) => format!("Input {}: Key image has already been spent.", index),
+            Self::RangeProof(e) => format!("Rangeproof: {}", e),
+            Self::Unspecified(msg) => msg.clone(),
+        })
+    }
+
+} impl Error for TransactionError {}
+
+///Key image store errors
+#[derive(Debug, Clone)]
+pub enum KeyImageStoreError {
+    ///The key image at the given index of the batch is already in the store,
+    ///or appears more than once in the batch.
+    AlreadySpent(usize),
+    ///The key image at the given index of the batch is not in the store.
+    NotFound(usize),
+    ///The underlying storage is corrupted.
+    Corrupted,
+    ///Failure to read from or write to the underlying storage.
+    StorageError(String),
+    ///Miscellaneous/unspecified error.
+    Unspecified(String)
+
+} impl Display for KeyImageStoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self{
+            Self::AlreadySpent(index) => format!("Key image {} has already been spent.", index),
+            Self::NotFound(index) => format!("Key image {} was not found.", index),
+            Self::Corrupted => "The key image store is corrupted.".to_string(),
+            Self::StorageError(msg) => format!("Storage error: {}", msg),
+            Self::Unspecified(msg) => msg.clone(),
+        })
+    }
+
+} impl Error for KeyImageStoreError {}
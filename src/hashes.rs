@@ -73,9 +73,26 @@ pub mod domains {
     pub const CLSAG_AUXILIARY: &[u8] =                  "clsag_aux".as_bytes();
     pub const CLSAG_COMMITMENT: &[u8] =                 "clsag_com".as_bytes();
 
+    pub const LSAG_KEY_IMAGE: &[u8] =                   "lsag_key_img".as_bytes();
+    pub const LSAG_COMMITMENT: &[u8] =                  "lsag_com".as_bytes();
+
+    pub const SAG_COMMITMENT: &[u8] =                   "sag_com".as_bytes();
+
+    pub const MULTISIG_KEY_AGGREGATION: &[u8] =         "multisig_agg".as_bytes();
+    pub const MULTISIG_NONCE: &[u8] =                   "multisig_nonce".as_bytes();
+
     pub const ECDH_VIEW_TAG: &[u8] =                    "ecdh_tag".as_bytes();
     pub const ECDH_ENCRYPTION_KEY: &[u8] =              "ecdh_enc".as_bytes();
+    pub const ECDH_KEY_OFFSET: &[u8] =                  "ecdh_offset".as_bytes();
+    pub const ECDH_BLINDING: &[u8] =                    "ecdh_blind".as_bytes();
+    pub const ECDH_AMOUNT_KEY: &[u8] =                  "ecdh_amount".as_bytes();
     pub const ECDH_PRIVATE_KEY: &[u8] =                 "ecdh_priv".as_bytes();
+    pub const ECDH_PAYMENT_ID: &[u8] =                  "ecdh_pid".as_bytes();
+    pub const ECDH_OUTPUT_INDEX: &[u8] =                "ecdh_index".as_bytes();
+
+    pub const OUTGOING_VIEW_KEY: &[u8] =                "out_view".as_bytes();
+    pub const OUTGOING_TRANSACTION_KEY: &[u8] =         "out_txn".as_bytes();
+    pub const OUTGOING_ENCRYPTION_KEY: &[u8] =          "out_enc".as_bytes();
 
     pub const CRYPTONOTE_PRIVATE_VIEW: &[u8] =          "cn_view".as_bytes();
     pub const CRYPTONOTE_PRIVATE_SPEND: &[u8] =         "cn_spend".as_bytes();
@@ -83,4 +100,21 @@ pub mod domains {
     pub const SUBADDRESS_MASTER_PRIVATE_VIEW: &[u8] =   "subaddr_mv".as_bytes();
     pub const SUBADDRESS_MASTER_PRIVATE_SPEND: &[u8] =  "subaddr_ms".as_bytes();
     pub const SUBADDRESS_SUB_PRIVATE_SPEND: &[u8] =     "subaddr_ss".as_bytes();
+
+    pub const ADDRESS_CHECKSUM: &[u8] =                 "addr_check".as_bytes();
+
+    pub const MNEMONIC_CHECKSUM: &[u8] =                "mnemonic_check".as_bytes();
+    pub const MNEMONIC_PASSPHRASE: &[u8] =              "mnemonic_pass".as_bytes();
+
+    pub const TRANSACTION_PREFIX: &[u8] =               "tx_prefix".as_bytes();
+    pub const KEY_IMAGE_STORE_CHECKSUM: &[u8] =         "key_img_store_check".as_bytes();
+
+    pub const ASSET_ID: &[u8] =                         "asset_id".as_bytes();
+    pub const ASSET_GENERATOR: &[u8] =                  "asset_gen".as_bytes();
+    pub const ASSET_SURJECTION: &[u8] =                 "asset_surj".as_bytes();
+
+    pub const PROOF_DLEQ: &[u8] =                       "proof_dleq".as_bytes();
+    pub const PROOF_PAYMENT: &[u8] =                    "proof_payment".as_bytes();
+    pub const PROOF_RESERVE: &[u8] =                    "proof_reserve".as_bytes();
+    pub const PROOF_KEY_IMAGE: &[u8] =                  "proof_key_img".as_bytes();
 }
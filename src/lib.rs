@@ -28,6 +28,9 @@ pub mod curve;
 pub mod rangeproof;
 pub mod signature;
 pub mod address;
+pub mod transaction;
+pub mod asset;
+pub mod proof;
 
 
 pub mod common {
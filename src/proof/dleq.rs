@@ -0,0 +1,63 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use zeroize::Zeroize;
+
+use crate::internal_common::*;
+
+///Calculate the challenge of a DLEQ proof.
+fn challenge(points: Vec<RistrettoPoint>, message: &[u8]) -> Scalar {
+    return domain_h_scalar(&[message, &batch_encode_points(&points).concat()].concat(), domains::PROOF_DLEQ)
+}
+
+
+///A discrete log equality (DLEQ) proof.
+///
+///Proves that `P_1 = x * B_1` and `P_2 = x * B_2` for the same secret `x`, without revealing `x`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DLEQProof {
+    c: Scalar,
+    s: Scalar
+
+} impl DLEQProof {
+    ///Create a DLEQ proof for `x`, given the bases `B_1` and `B_2`, and a message which the proof is bound to.
+    pub fn prove(x: &Scalar, base_1: &RistrettoPoint, base_2: &RistrettoPoint, message: &[u8]) -> Self {
+        let mut k = Scalar::generate();
+        let c = challenge(vec!(
+            *base_1, x * base_1,
+            *base_2, x * base_2,
+            k * base_1, k * base_2
+        ), message);
+        let s = k - (c * x);
+
+        k.zeroize();
+
+        return Self{c, s}
+    }
+
+    ///Given a DLEQ proof, the bases `B_1` and `B_2`, the public keys `P_1` and `P_2`, and the message, check if it's valid.
+    ///
+    ///Returns `Ok()` if the proof is valid,
+    ///or `Err(SignatureError)` if it's invalid.
+    pub fn verify(
+        base_1: &RistrettoPoint, public_1: &RistrettoPoint, base_2: &RistrettoPoint, public_2: &RistrettoPoint,
+        message: &[u8], proof: &DLEQProof
+    ) -> Result<(), SignatureError> {
+        //k * B = (s * B) + (c * P)
+        let c = challenge(vec!(
+            *base_1, *public_1,
+            *base_2, *public_2,
+            (proof.s * base_1) + (proof.c * public_1),
+            (proof.s * base_2) + (proof.c * public_2)
+        ), message);
+
+        return match c == proof.c {
+            true => Ok(()),
+            false => Err(SignatureError::Invalid)
+        };
+    }
+
+} impl ToBytes<'_> for DLEQProof {}
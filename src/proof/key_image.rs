@@ -0,0 +1,144 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::internal_common::*;
+use crate::signature::{
+    get_key_image,
+    key_image_point
+};
+use crate::transaction::KeyImageStore;
+use super::DLEQProof;
+
+///Create the proven message: a hash of the public key and key image.
+fn create_message(public_key: &RistrettoPoint, key_image: &RistrettoPoint) -> [u8; 32] {
+    return domain_h_bytes(
+        &batch_encode_points(&vec!(*public_key, *key_image)).concat(),
+        domains::PROOF_KEY_IMAGE
+    )
+}
+
+
+///A key image, signed by the owner of an enote.
+///
+///Includes a DLEQ proof that `x * G = P` and `x * Hp(P) = I`,
+///proving that the key image belongs to the enote's owner key `P`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SignedKeyImage {
+    pub public_key: RistrettoPoint,
+    pub key_image: RistrettoPoint,
+    proof: DLEQProof
+
+} impl SignedKeyImage {
+    ///Sign the key image of an enote, given its private keys.
+    pub fn sign(enote_keys: &EnoteKeys) -> Self {
+        let public_key = &enote_keys.owner * G;
+        let key_image = get_key_image(enote_keys.owner);
+        let m = create_message(&public_key, &key_image);
+        let proof = DLEQProof::prove(&enote_keys.owner, &G_POINT, &key_image_point(&public_key), &m);
+        return Self{public_key, key_image, proof}
+    }
+
+    ///Sign the key images of enotes, for exporting to a view-only wallet.
+    pub fn export(enote_keys: &[EnoteKeys]) -> Vec<Self> {
+        return enote_keys.iter().map(Self::sign).collect()
+    }
+
+    ///Check if this signed key image is valid.
+    ///
+    ///Returns `Ok()` if the signature is valid,
+    ///or `Err(SignatureError)` if it's invalid.
+    pub fn verify(&self) -> Result<(), SignatureError> {
+        let m = create_message(&self.public_key, &self.key_image);
+        return DLEQProof::verify(
+            &G_POINT, &self.public_key, &key_image_point(&self.public_key), &self.key_image, &m, &self.proof
+        )
+    }
+
+} impl ToBytes<'_> for SignedKeyImage {}
+
+
+///An enote received by a view-only wallet.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WatchedEnote {
+    pub enote: Enote,
+    ///The key image, if imported
+    pub key_image: Option<RistrettoPoint>,
+    pub spent: bool
+
+} impl ToBytes<'_> for WatchedEnote {}
+
+///The enotes received by a view-only wallet, and their spent status.
+///
+///View-only wallets can't calculate key images, so they are imported from the spend wallet (see `SignedKeyImage::export`),
+///and then checked against the set of spent key images.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WatchedEnotes {
+    enotes: Vec<WatchedEnote>
+
+} impl WatchedEnotes {
+    ///Create an empty set of enotes.
+    pub fn new() -> Self {
+        return Self::default()
+    }
+
+    ///Add a received enote, if it is not already included.
+    pub fn add(&mut self, enote: Enote) {
+        if self.enotes.iter().any(|watched| watched.enote.owner == enote.owner) {
+            return
+        }
+        self.enotes.push(WatchedEnote{enote, key_image: None, spent: false});
+    }
+
+    ///Return all enotes, in the order they were added.
+    pub fn get_enotes(&self) -> &[WatchedEnote] {
+        return &self.enotes
+    }
+
+    ///Return the enotes which are not known to be spent.
+    pub fn unspent(&self) -> Vec<Enote> {
+        return self.enotes.iter().filter(|watched| !watched.spent).map(|watched| watched.enote).collect()
+    }
+
+    ///Import signed key images, verifying each against its enote.
+    ///
+    ///Signed key images for enotes which are not included are ignored.
+    ///
+    ///Returns the number of imported key images,
+    ///or `Err(SignatureError)` if any signature is invalid, in which case nothing is imported.
+    pub fn import_key_images(&mut self, signed_key_images: &[SignedKeyImage]) -> Result<usize, SignatureError> {
+        let mut imported: Vec<(usize, RistrettoPoint)> = Vec::new();
+        for signed in signed_key_images {
+            let index = match self.enotes.iter().position(|watched| watched.enote.owner == signed.public_key) {
+                Some(index) => index,
+                None => continue
+            };
+            signed.verify()?;
+            imported.push((index, signed.key_image));
+        }
+
+        for (index, key_image) in &imported {
+            self.enotes[*index].key_image = Some(*key_image);
+        }
+        return Ok(imported.len())
+    }
+
+    ///Mark the enotes whose key images are in the set of spent key images as spent.
+    ///
+    ///Returns the newly spent enotes.
+    pub fn update_spent(&mut self, spent: &impl KeyImageStore) -> Vec<Enote> {
+        let mut newly_spent: Vec<Enote> = Vec::new();
+        for watched in self.enotes.iter_mut().filter(|watched| !watched.spent) {
+            if let Some(key_image) = watched.key_image {
+                if spent.contains(&key_image) {
+                    watched.spent = true;
+                    newly_spent.push(watched.enote);
+                }
+            }
+        }
+        return newly_spent
+    }
+
+} impl ToBytes<'_> for WatchedEnotes {}
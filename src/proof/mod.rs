@@ -0,0 +1,31 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Proofs about payments and enotes, which can be checked by a third party.
+//!
+//! A `PaymentProof` shows that an output was sent to an address with a given amount,
+//! for example when a payment is disputed.
+//! It is built from a `DLEQProof` over the shared secret point.
+//!
+//! A `ReserveProof` shows ownership of a set of unspent enotes, whose total value is at least a given amount.
+//!
+//! A `SignedKeyImage` proves that a key image belongs to an enote.
+//! Spend wallets export them, so that view-only wallets, which can't calculate key images,
+//! can import them into `WatchedEnotes` and detect which of their enotes are spent.
+
+mod dleq;
+mod payment;
+mod reserve;
+mod key_image;
+
+pub use dleq::DLEQProof;
+pub use payment::PaymentProof;
+pub use reserve::ReserveProof;
+pub use key_image::{
+    SignedKeyImage,
+    WatchedEnote,
+    WatchedEnotes
+};
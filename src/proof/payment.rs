@@ -0,0 +1,124 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::internal_common::*;
+use crate::address::{
+    Address,
+    Recipient,
+    SharedSecret,
+    DerivationVersion
+};
+use super::DLEQProof;
+
+///Return the base of the transaction key, and the public view key of an address.
+///
+///The transaction key is `r * G` for CryptoNote addresses, and `r * D` for subaddresses.
+fn get_keys(address: &Address) -> (RistrettoPoint, RistrettoPoint) {
+    return match address {
+        Address::CryptoNote(address) => (G_POINT, address.view),
+        Address::Integrated(address) => (G_POINT, address.address.view),
+        Address::Subaddress(address) => (address.spend, address.view)
+    }
+}
+
+///Return the public spend key of an address.
+fn get_spend(address: &Address) -> RistrettoPoint {
+    return match address {
+        Address::CryptoNote(address) => address.spend,
+        Address::Integrated(address) => address.address.spend,
+        Address::Subaddress(address) => address.spend
+    }
+}
+
+///Create the proven message: a hash of the address, the transaction key, the output's public key, and its index.
+fn create_message(address: &Address, transaction_key: &RistrettoPoint, recipient: &Recipient, index: Option<u64>) -> [u8; 32] {
+    let (base, view) = get_keys(address);
+    let mut framing: Vec<u8> = Vec::new();
+    match index {
+        Some(index) => {
+            framing.push(1);
+            framing.extend(index.to_le_bytes());
+        },
+        None => framing.push(0)
+    }
+    let points = vec!(base, view, get_spend(address), *transaction_key, recipient.public_key);
+    return domain_h_bytes(&[framing, batch_encode_points(&points).concat()].concat(), domains::PROOF_PAYMENT)
+}
+
+///Derive the shared secret of an output from the shared secret point.
+fn get_shared_secret(point: &RistrettoPoint, index: Option<u64>) -> SharedSecret {
+    let shared_secret = SharedSecret::from_point(point);
+    return match index {
+        Some(index) => shared_secret.with_output_index(index),
+        None => shared_secret
+    }
+}
+
+
+///A payment proof.
+///
+///Proves that an output was sent to an address, with a given amount.
+///This is created by the sender, using the transaction private key,
+///and includes the shared secret point with a DLEQ proof that it matches the transaction key and the address' view key.
+///
+///Note that the proof reveals the output's amount and blinding factor to the verifier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentProof {
+    shared_secret: RistrettoPoint,
+    index: Option<u64>,
+    proof: DLEQProof
+
+} impl PaymentProof {
+    ///Create a payment proof, given the transaction private key, the address the output was sent to, and the output.
+    ///
+    ///`index` is the index of the output, if its transaction key is shared between multiple outputs (see `send_multiple`).
+    ///
+    ///Returns a payment proof if proving was successful,
+    ///or `SignatureError::Malformed` if the output was not sent to this address with this transaction key.
+    pub fn prove(
+        transaction_sk: &Scalar, address: &Address, recipient: &Recipient, index: Option<u64>
+    ) -> Result<Self, SignatureError> {
+        let (base, view) = get_keys(address);
+        let transaction_key = transaction_sk * base;
+        let shared_secret = transaction_sk * view;
+
+        //check public key
+        let derived = get_shared_secret(&shared_secret, index);
+        let spend = get_spend(address);
+        if !DerivationVersion::ALL.iter()
+            .any(|version| spend + (&derived.key_offset(*version) * G) == recipient.public_key) {
+            return Err(SignatureError::Malformed)
+        }
+
+        let m = create_message(address, &transaction_key, recipient, index);
+        let proof = DLEQProof::prove(transaction_sk, &base, &view, &m);
+        return Ok(Self{shared_secret, index, proof})
+    }
+
+    ///Given a payment proof, the address, the output's transaction key, the output, and its pedersen commitment,
+    ///check if it's valid.
+    ///
+    ///Returns `Ok(amount)` if the proof is valid,
+    ///or `Err(SignatureError)` if it's invalid.
+    pub fn verify(
+        address: &Address, transaction_key: &RistrettoPoint, recipient: &Recipient, commitment: &Commitment, proof: &PaymentProof
+    ) -> Result<u64, SignatureError> {
+        //check that the shared secret matches the transaction key and the view key
+        let (base, view) = get_keys(address);
+        let m = create_message(address, transaction_key, recipient, proof.index);
+        DLEQProof::verify(&base, transaction_key, &view, &proof.shared_secret, &m, &proof.proof)?;
+
+        //check public key and commitment
+        let spend = get_spend(address);
+        let shared_secret = get_shared_secret(&proof.shared_secret, proof.index);
+        return match shared_secret.open_enote(recipient, commitment, |offset|
+            (spend + (offset * G) == recipient.public_key).then_some(())) {
+            Some((_, _, value, _)) => Ok(value),
+            None => Err(SignatureError::Invalid)
+        }
+    }
+
+} impl ToBytes<'_> for PaymentProof {}
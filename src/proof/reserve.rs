@@ -0,0 +1,134 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use zeroize::Zeroize;
+
+use crate::internal_common::*;
+use crate::rangeproof::BulletPlusRangeProof;
+use crate::signature::{
+    get_key_image,
+    key_image_point
+};
+use crate::transaction::KeyImageStore;
+use super::DLEQProof;
+
+///Create the proven message: a hash of the message, the amount, the enotes, and their key images.
+fn create_message(enotes: &[Enote], key_images: &[RistrettoPoint], amount: u64, message: &[u8]) -> [u8; 32] {
+    let mut points: Vec<RistrettoPoint> = Vec::new();
+    for enote in enotes {
+        points.push(enote.owner);
+        points.push(enote.commitment.0);
+    }
+    points.extend(key_images);
+    let framing = [(enotes.len() as u64).to_le_bytes(), amount.to_le_bytes()].concat();
+    return domain_h_bytes(
+        &[h_bytes(message).as_slice(), &framing, &batch_encode_points(&points).concat()].concat(),
+        domains::PROOF_RESERVE
+    )
+}
+
+///Return the sum of the enotes' commitments.
+fn total_commitment(enotes: &[Enote]) -> Commitment {
+    return Commitment(enotes.iter().map(|enote| enote.commitment.0).sum())
+}
+
+///Check that there are no duplicate key images (ie, no enote is included twice).
+fn has_duplicates(key_images: &[RistrettoPoint]) -> bool {
+    let mut encoded = batch_encode_points(&key_images.to_vec());
+    encoded.sort_unstable();
+    encoded.dedup();
+    return encoded.len() != key_images.len()
+}
+
+
+///A reserve proof.
+///
+///Proves ownership of a set of enotes, whose total value is at least a given amount,
+///without revealing the total value.
+///The proof includes the standard key image of each enote with a DLEQ proof that it matches the enote's owner key,
+///so the verifier can check that none of the enotes are spent (see `is_unspent`).
+///The proof is bound to a message, such as a challenge from the verifier, to prevent replaying old proofs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReserveProof {
+    key_images: Vec<RistrettoPoint>,
+    ownership: Vec<DLEQProof>,
+    rangeproof: BulletPlusRangeProof
+
+} impl ReserveProof {
+    ///Create a reserve proof, given the enotes' private keys, the minimum amount, and a message.
+    ///
+    ///Returns a reserve proof if proving was successful,
+    ///or `SignatureError::Malformed` if there are no enotes, duplicate enotes, or the total value is less than `amount`.
+    pub fn prove(enote_keys: &[EnoteKeys], amount: u64, message: &[u8]) -> Result<Self, SignatureError> {
+        if enote_keys.is_empty() {
+            return Err(SignatureError::Malformed)
+        }
+        let total = enote_keys.iter()
+            .try_fold(0u64, |total, keys| total.checked_add(keys.value))
+            .ok_or(SignatureError::Malformed)?;
+        if total < amount {
+            return Err(SignatureError::Malformed)
+        }
+
+        let enotes: Vec<Enote> = enote_keys.iter().map(|keys| keys.to_enote()).collect();
+        let key_images: Vec<RistrettoPoint> = enote_keys.iter().map(|keys| get_key_image(keys.owner)).collect();
+        if has_duplicates(&key_images) {
+            return Err(SignatureError::Malformed)
+        }
+
+        //x * G = P and x * Hp(P) = I
+        let m = create_message(&enotes, &key_images, amount, message);
+        let ownership = enote_keys.iter().zip(&enotes)
+            .map(|(keys, enote)| DLEQProof::prove(&keys.owner, &G_POINT, &key_image_point(&enote.owner), &m))
+            .collect();
+
+        //the total commitment is proven to be at least the amount
+        let mut blinding: Scalar = enote_keys.iter().map(|keys| keys.blinding).sum();
+        let rangeproof = BulletPlusRangeProof::prove_with_minimums(vec!(total), vec!(blinding), vec!(amount));
+        blinding.zeroize();
+        let (_, rangeproof) = rangeproof.map_err(|_| SignatureError::Malformed)?;
+
+        return Ok(Self{key_images, ownership, rangeproof})
+    }
+
+    ///Given a reserve proof, the enotes, the minimum amount, and the message, check if it's valid.
+    ///
+    ///Note that this does not check if the enotes are unspent, see `is_unspent`.
+    ///
+    ///Returns `Ok()` if the proof is valid,
+    ///or `Err(SignatureError)` if it's invalid.
+    pub fn verify(enotes: &[Enote], amount: u64, message: &[u8], proof: &ReserveProof) -> Result<(), SignatureError> {
+        let n = enotes.len();
+        if n == 0 || proof.key_images.len() != n || proof.ownership.len() != n || has_duplicates(&proof.key_images) {
+            return Err(SignatureError::Malformed)
+        }
+
+        let m = create_message(enotes, &proof.key_images, amount, message);
+        for ((enote, key_image), ownership) in enotes.iter().zip(&proof.key_images).zip(&proof.ownership) {
+            DLEQProof::verify(&G_POINT, &enote.owner, &key_image_point(&enote.owner), key_image, &m, ownership)?;
+        }
+
+        return match BulletPlusRangeProof::verify_with_minimums(
+            vec!(total_commitment(enotes)), vec!(amount), proof.rangeproof.clone()
+        ) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(SignatureError::Invalid)
+        }
+    }
+
+    ///Return the key images of the enotes, in order.
+    pub fn get_key_images(&self) -> &[RistrettoPoint] {
+        return &self.key_images
+    }
+
+    ///Check that none of the enotes are spent, given the set of spent key images.
+    ///
+    ///This should only be used after the proof is verified.
+    pub fn is_unspent(&self, spent: &impl KeyImageStore) -> bool {
+        return !self.key_images.iter().any(|key_image| spent.contains(key_image))
+    }
+
+} impl ToBytes<'_> for ReserveProof {}
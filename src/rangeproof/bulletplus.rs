@@ -6,11 +6,16 @@
 
 //! Bulletproofs+ rangeproofs
 
-use crate::{internal_common::*, rangeproof::MAX_VALUE};
+use crate::internal_common::*;
+use crate::asset::AssetTag;
 use super::{
     BIT_RANGE, MAX_AGGREGATION_SIZE
 };
-use std::iter::zip;
+use std::{
+    collections::BTreeMap,
+    iter::zip,
+    sync::OnceLock
+};
 
 use bulletproofs_plus::{
     range_parameters::RangeParameters,
@@ -36,27 +41,38 @@ const MAX_BATCH_GROUP_SIZE: usize = 256;
 const EXTENSION_DEGREE: ExtensionDegree = ExtensionDegree::DefaultPedersen;
 const TRANSCRIPT_LABEL: &'static str = "Bulletproofs+ Rangeproofs";
 
+///Number of supported bit ranges: 1, 2, 4, ... `BIT_RANGE`
+const BIT_RANGE_COUNT: usize = BIT_RANGE.trailing_zeros() as usize + 1;
+
+///Range parameters for each supported bit range, generated when first used
+static RANGE_PARAMETERS: [OnceLock<Vec<RangeParameters<RistrettoPoint>>>; BIT_RANGE_COUNT] =
+    [const { OnceLock::new() }; BIT_RANGE_COUNT];
+
 lazy_static! {
-    static ref RANGE_PARAMETERS: Vec<RangeParameters<RistrettoPoint>> = generate_range_parameters();
     static ref ZERO_COMMITMENT_OPENING: CommitmentOpening = CommitmentOpening::new(0, vec!(Scalar::zero()));
     static ref ZERO_COMMITMENT: Commitment = Commitment(&Scalar::zero() * G);
 }
 
-/// pre-generate range parameters
-fn generate_range_parameters() -> Vec<RangeParameters<RistrettoPoint>> {
-    let pedersen_gens: PedersenGens<RistrettoPoint> = PedersenGens {
-        h_base: *PEDERSEN_H_POINT,
-        h_base_compressed: PEDERSEN_H_POINT.compress(),
+/// get pedersen generators, using `h_base` for values
+fn get_pedersen_gens(h_base: RistrettoPoint) -> PedersenGens<RistrettoPoint> {
+    return PedersenGens {
+        h_base,
+        h_base_compressed: h_base.compress(),
         g_base_vec: vec!(*PEDERSEN_G_POINT),
         g_base_compressed_vec: vec!(PEDERSEN_G_POINT.compress()),
         extension_degree: EXTENSION_DEGREE
-    };
+    }
+}
+
+/// generate range parameters for a bit range
+fn generate_range_parameters(bit_range: usize) -> Vec<RangeParameters<RistrettoPoint>> {
+    let pedersen_gens = get_pedersen_gens(*PEDERSEN_H_POINT);
 
     let max_agg_factor = (MAX_AGGREGATION_SIZE as f64).log2() as usize;
     let mut result: Vec<RangeParameters<RistrettoPoint>> = Vec::new();
     for i in 0 .. max_agg_factor + 1 {
         result.push(RangeParameters::init(
-            BIT_RANGE,
+            bit_range,
             2usize.pow(i as u32),
             pedersen_gens.clone(),
         ).expect("failed to generate range parameters"));
@@ -65,23 +81,146 @@ fn generate_range_parameters() -> Vec<RangeParameters<RistrettoPoint>> {
 }
 
 
+///Get the range parameters for a bit range, generating them if this is the first time they're used.
+///
+///The bit range must already have been checked with `check_bit_range`.
+fn get_range_parameters(bit_range: usize) -> &'static [RangeParameters<RistrettoPoint>] {
+    return RANGE_PARAMETERS[bit_range.trailing_zeros() as usize]
+        .get_or_init(|| generate_range_parameters(bit_range))
+}
+
+///Check that a bit range is supported: a power of 2, and no larger than `BIT_RANGE`.
+fn check_bit_range(bit_range: usize) -> Result<(), RangeProofError> {
+    if !bit_range.is_power_of_two() || bit_range > BIT_RANGE {
+        return Err(RangeProofError::Malformed)
+    }
+    return Ok(())
+}
+
+///Pad commitments (and their minimum value promises) to the next power of 2 and create a statement for them.
+fn get_statement(commitments: &[Commitment], minimums: &[Option<u64>], bit_range: usize, seed_nonce: Option<Scalar>
+) -> Result<RangeStatement<RistrettoPoint>, TariProofError> {
+    //power = closest value of log_2( commitments.len() ), rounded up
+    let power = (commitments.len() as f64).log2().ceil();
+    //n = closest power of 2, rounded up
+    let n = 1 << (power as u32);
+    //pad_len = distance to closest power of 2, rounded up
+    let pad_len = n - commitments.len();
+
+    //commitments must be padded to the next power of 2
+    let padded_commitments = [
+        vec![*ZERO_COMMITMENT; pad_len], commitments.to_owned()
+    ].concat();
+    let padded_commitments = Commitment::to_ristretto(padded_commitments);
+
+    let padded_minimums = [
+        vec![None; pad_len], minimums.to_owned()
+    ].concat();
+
+    return RangeStatement::init(
+        get_range_parameters(bit_range)[power as usize].to_owned(), padded_commitments, padded_minimums, seed_nonce
+    )
+}
+
+///Create a statement for a single asset commitment, using its asset tag in place of `H`.
+///
+///The bulletproof generators are the same as for non-asset proofs, so they are reused.
+fn get_asset_statement(commitment: Commitment, asset_tag: &AssetTag
+) -> Result<RangeStatement<RistrettoPoint>, TariProofError> {
+    let range_parameters = get_range_parameters(BIT_RANGE)[0].with_pc_gens(get_pedersen_gens(asset_tag.0));
+    return RangeStatement::init(range_parameters, vec!(commitment.0), vec!(None), None)
+}
+
+
 ///Bulletproofs+ rangeproof.
 ///
 ///These proofs scale logarithmically, and support highly efficient batch verification.
+///
+///By default, proofs use a bit range of `BIT_RANGE`.
+///Smaller bit ranges are supported using `prove_with_bit_range`.
+///The bit range is included in the proof, and must match the bit range used when verifying.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BulletPlusRangeProof (
-    TariRangeProof<RistrettoPoint>
+pub struct BulletPlusRangeProof {
+    proof: TariRangeProof<RistrettoPoint>,
+    bit_range: usize
+
+} impl BulletPlusRangeProof {
+    ///Return the bit range this proof was created with.
+    pub fn bit_range(&self) -> usize {
+        return self.bit_range
+    }
 
-); impl BulletPlusRangeProof {
     ///Create a Bulletproofs+ rangeproof, given values and blinding factors.
     ///
     ///Return a vector of commitments and a BP+ rangeproof if proving was successful,
     ///or `RangeProofError` if an error occurred.
     pub fn prove(values: Vec<u64>, blindings: Vec<Scalar>
+    ) -> Result<(Vec<Commitment>, Self), RangeProofError> {
+        let minimums = vec![None; values.len()];
+        return Self::prove_internal(values, blindings, minimums, BIT_RANGE, None)
+    }
+
+    ///Create a Bulletproofs+ rangeproof with a custom bit range, given values and blinding factors.
+    ///
+    ///The bit range must be a power of 2, no larger than `BIT_RANGE`,
+    ///and each value must be less than 2<sup>`bit_range`</sup>.
+    ///Smaller bit ranges result in smaller proofs, which are faster to create and verify.
+    ///
+    ///Return a vector of commitments and a BP+ rangeproof if proving was successful,
+    ///or `RangeProofError` if an error occurred.
+    pub fn prove_with_bit_range(values: Vec<u64>, blindings: Vec<Scalar>, bit_range: usize
+    ) -> Result<(Vec<Commitment>, Self), RangeProofError> {
+        let minimums = vec![None; values.len()];
+        return Self::prove_internal(values, blindings, minimums, bit_range, None)
+    }
+
+    ///Create a Bulletproofs+ rangeproof, given values, blinding factors, and a public minimum value for each commitment.
+    ///
+    ///The proof shows that `minimum <= value < minimum + 2^BIT_RANGE` for each commitment,
+    ///without revealing anything else about the value.
+    ///The same minimums must be given to `verify_with_minimums`.
+    ///
+    ///Return a vector of commitments and a BP+ rangeproof if proving was successful,
+    ///or `RangeProofError` if an error occurred.
+    pub fn prove_with_minimums(values: Vec<u64>, blindings: Vec<Scalar>, minimums: Vec<u64>
+    ) -> Result<(Vec<Commitment>, Self), RangeProofError> {
+        if values.len() != minimums.len() {
+            return Err(RangeProofError::Malformed)
+        }
+        for (value, minimum) in zip(&values, &minimums) {
+            if value < minimum {
+                return Err(RangeProofError::OutOfRange)
+            }
+        }
+        let minimums = minimums.into_iter().map(Some).collect();
+        return Self::prove_internal(values, blindings, minimums, BIT_RANGE, None)
+    }
+
+    ///Create a (non-aggregated) Bulletproofs+ rangeproof, given a value, a blinding factor, and a seed nonce.
+    ///
+    ///The blinding factor can be recovered from the proof by anyone who knows the seed nonce, see `recover_blinding`.
+    ///This can be used to restore a wallet's enotes without relying on any other encrypted data.
+    ///
+    ///**The seed nonce must be secret, and should never be reused.**
+    ///It should be derived deterministically from a secret only the recipient knows, such as an ECDH shared secret.
+    ///
+    ///Return a commitment and a BP+ rangeproof if proving was successful,
+    ///or `RangeProofError` if an error occurred.
+    pub fn prove_with_seed(value: u64, blinding: Scalar, seed_nonce: Scalar
+    ) -> Result<(Commitment, Self), RangeProofError> {
+        let (commitments, proof) = Self::prove_internal(
+            vec!(value), vec!(blinding), vec!(None), BIT_RANGE, Some(seed_nonce))?;
+        return Ok((commitments[0], proof))
+    }
+
+    ///Internal proving function.
+    fn prove_internal(
+        values: Vec<u64>, blindings: Vec<Scalar>, minimums: Vec<Option<u64>>, bit_range: usize, seed_nonce: Option<Scalar>
     ) -> Result<(Vec<Commitment>, Self), RangeProofError> {
 
         //wrapped so we don't have to deal wtih TariProofError
-        fn inner(values: Vec<u64>, blindings: Vec<Scalar>
+        fn inner(
+            values: Vec<u64>, blindings: Vec<Scalar>, minimums: Vec<Option<u64>>, bit_range: usize, seed_nonce: Option<Scalar>
         ) -> Result<(Vec<Commitment>, BulletPlusRangeProof), TariProofError> {
 
             let mut commitment_openings: Vec<CommitmentOpening> = Vec::new();
@@ -93,33 +232,20 @@ pub struct BulletPlusRangeProof (
                 commitments.push(Commitment::commit(value, blinding));
             }
 
-            //power = closest value of log_2( commitments.len() ), rounded up
-            let power = (commitments.len() as f64).log2().ceil();
-            //n = closest power of 2, rounded up
-            let n = 1 << (power as u32);
-            //pad_len = distance to closest power of 2, rounded up
-            let pad_len = n - commitments.len();
-            //commitments must be padded to the next power of 2
+            let statement = get_statement(&commitments, &minimums, bit_range, seed_nonce)?;
+
+            //openings must be padded the same way as the commitments
+            let pad_len = statement.commitments.len() - commitments.len();
             let padded_openings = [
                 vec![ZERO_COMMITMENT_OPENING.clone(); pad_len], commitment_openings
             ].concat();
-            let padded_commitments = [
-                vec![*ZERO_COMMITMENT; pad_len], commitments.to_owned()
-            ].concat();
-            let padded_commitments = Commitment::to_ristretto(padded_commitments);
-
             let witness = RangeWitness::init(padded_openings)?;
 
-            let none_vec = vec![None; n];
-            let statement = RangeStatement::init(
-                RANGE_PARAMETERS[power as usize].to_owned(), padded_commitments, none_vec, None
-            )?;
-
             let proof = TariRangeProof::prove(
                 TRANSCRIPT_LABEL, &statement, &witness
             )?;
 
-            return Ok((commitments, BulletPlusRangeProof(proof)))
+            return Ok((commitments, BulletPlusRangeProof{proof, bit_range}))
         }
 
         if values.len() != blindings.len() {
@@ -128,12 +254,15 @@ pub struct BulletPlusRangeProof (
         if values.len() > MAX_AGGREGATION_SIZE {
             return Err(RangeProofError::TooLargeAggregationSize)
         }
-        for value in &values {
-            if value > &MAX_VALUE {
+        check_bit_range(bit_range)?;
+        //the value is proven to be in range after subtracting the minimum
+        let max_value = ((1u128 << bit_range) - 1) as u64;
+        for (value, minimum) in zip(&values, &minimums) {
+            if value - minimum.unwrap_or(0) > max_value {
                 return Err(RangeProofError::OutOfRange)
             }
         }
-        return match inner(values, blindings) {
+        return match inner(values, blindings, minimums, bit_range, seed_nonce) {
             Ok(proof) => Ok(proof),
             Err(_) => Err(
                 RangeProofError::Unspecified("failed to create rangeproof".to_string())
@@ -152,6 +281,30 @@ pub struct BulletPlusRangeProof (
         return Self::batch_verify(vec!(commitments), vec!(proof));
     }
 
+    ///Verify a Bulletproofs+ rangeproof created by `prove_with_minimums`,
+    ///given its associated commitments and their minimum values.
+    ///
+    ///Returns `Ok()` if the proof is valid,
+    ///or `Err(RangeProofError)` if it's invalid.
+    ///
+    ///`batch_verify_with_minimums` should be preferred when verifying multiple proofs.
+    pub fn verify_with_minimums(commitments: Vec<Commitment>, minimums: Vec<u64>, proof: BulletPlusRangeProof
+    ) -> Result<(), RangeProofError> {
+        return Self::batch_verify_with_minimums(vec!(commitments), vec!(minimums), vec!(proof));
+    }
+
+    ///Verify a Bulletproofs+ rangeproof created by `prove_with_bit_range`,
+    ///given its associated commitments and bit range.
+    ///
+    ///Returns `Ok()` if the proof is valid,
+    ///or `Err(RangeProofError)` if it's invalid.
+    ///
+    ///`batch_verify_with_bit_ranges` should be preferred when verifying multiple proofs.
+    pub fn verify_with_bit_range(commitments: Vec<Commitment>, bit_range: usize, proof: BulletPlusRangeProof
+    ) -> Result<(), RangeProofError> {
+        return Self::batch_verify_with_bit_ranges(vec!(commitments), vec!(bit_range), vec!(proof));
+    }
+
     ///Batch-verify several Bulletproofs+ rangeproofs given their associated commitments.
     ///
     ///Returns `Ok()` if the proof is valid,
@@ -159,66 +312,107 @@ pub struct BulletPlusRangeProof (
     ///
     ///Batch verification provides significant performance gains.
     pub fn batch_verify(commitments: Vec<Vec<Commitment>>, proofs: Vec<BulletPlusRangeProof>
+    ) -> Result<(), RangeProofError> {
+        let minimums = commitments.iter()
+            .map(|commitment_group| vec![None; commitment_group.len()]).collect();
+        let bit_ranges = vec![BIT_RANGE; commitments.len()];
+        return Self::batch_verify_internal(commitments, minimums, bit_ranges, proofs)
+    }
+
+    ///Batch-verify several Bulletproofs+ rangeproofs created by `prove_with_minimums`,
+    ///given their associated commitments and minimum values.
+    ///
+    ///Returns `Ok()` if the proof is valid,
+    ///or `Err(RangeProofError)` if it's invalid.
+    ///
+    ///Proofs without minimums can be included in the same batch, by using a minimum of 0.
+    pub fn batch_verify_with_minimums(
+        commitments: Vec<Vec<Commitment>>, minimums: Vec<Vec<u64>>, proofs: Vec<BulletPlusRangeProof>
+    ) -> Result<(), RangeProofError> {
+        if commitments.len() != minimums.len() {
+            return Err(RangeProofError::Malformed)
+        }
+        for (commitment_group, minimum_group) in zip(&commitments, &minimums) {
+            if commitment_group.len() != minimum_group.len() {
+                return Err(RangeProofError::Malformed)
+            }
+        }
+        let minimums = minimums.into_iter()
+            .map(|minimum_group| minimum_group.into_iter().map(Some).collect()).collect();
+        let bit_ranges = vec![BIT_RANGE; commitments.len()];
+        return Self::batch_verify_internal(commitments, minimums, bit_ranges, proofs)
+    }
+
+    ///Batch-verify several Bulletproofs+ rangeproofs created by `prove_with_bit_range`,
+    ///given their associated commitments and bit ranges.
+    ///
+    ///Returns `Ok()` if the proof is valid,
+    ///or `Err(RangeProofError)` if it's invalid.
+    ///
+    ///Proofs with different bit ranges can be included in the same batch,
+    ///but batch verification is most efficient when many proofs share the same bit range.
+    pub fn batch_verify_with_bit_ranges(
+        commitments: Vec<Vec<Commitment>>, bit_ranges: Vec<usize>, proofs: Vec<BulletPlusRangeProof>
+    ) -> Result<(), RangeProofError> {
+        let minimums = commitments.iter()
+            .map(|commitment_group| vec![None; commitment_group.len()]).collect();
+        return Self::batch_verify_internal(commitments, minimums, bit_ranges, proofs)
+    }
+
+    ///Internal batch verification function.
+    fn batch_verify_internal(
+        commitments: Vec<Vec<Commitment>>, minimums: Vec<Vec<Option<u64>>>,
+        bit_ranges: Vec<usize>, proofs: Vec<BulletPlusRangeProof>
     ) -> Result<(), RangeProofError> {
 
         //wrapped so we don't have to deal wtih TariProofError
-        fn inner(commitments: Vec<Vec<Commitment>>, proofs: Vec<BulletPlusRangeProof>
+        fn inner(
+            commitments: Vec<Vec<Commitment>>, minimums: Vec<Vec<Option<u64>>>,
+            bit_ranges: Vec<usize>, proofs: Vec<BulletPlusRangeProof>
         ) -> Result<(), TariProofError> {
-            let mut statements: Vec<RangeStatement<RistrettoPoint>>;
-
-            //power = closest value of log_2( commitment_group.len() ), rounded up
-            let mut power: f64;
-            //n = closest power of 2, rounded up
-            let mut n: usize;
-            //pad_len = distance to closest power of 2, rounded up
-            let mut pad_len: usize;
-
-            let mut padded_commitments: Vec<Commitment>;
-            let mut none_vec: Vec<Option<u64>>;
-            //extracted TariRangeProofs from BulletPlusRangeProof
-            let mut _proofs: Vec<TariRangeProof<RistrettoPoint>>;
-
-            //Split the proofs and commitments into smaller batches
-            //Tari's BP+ implementation limits batch sizes to 256
-            //This is a way to get around that
-            for (commitment_group, proof_group) in zip(
-                commitments.chunks(MAX_BATCH_GROUP_SIZE), proofs.chunks(MAX_BATCH_GROUP_SIZE)
+            //statements, and extracted TariRangeProofs from BulletPlusRangeProof
+            type Group = (Vec<RangeStatement<RistrettoPoint>>, Vec<TariRangeProof<RistrettoPoint>>);
+
+            //Tari's BP+ implementation requires every proof in a batch to have the same bit range,
+            //so the proofs are grouped by bit range
+            let mut groups: BTreeMap<usize, Group> = BTreeMap::new();
+            for (((coms, mins), bit_range), proof) in zip(zip(zip(
+                &commitments, &minimums), bit_ranges), proofs
             ) {
-                statements = Vec::new();
-                for coms in commitment_group {
-                    power = (coms.len() as f64).log2().ceil();
-                    n = 1 << (power as u32);
-                    pad_len = n - coms.len();
-
-                    //commitments must be padded to the next power of 2
-                    padded_commitments = [
-                        vec![*ZERO_COMMITMENT; pad_len], coms.to_owned()
-                    ].concat();
-                    let padded_commitments = Commitment::to_ristretto(padded_commitments);
-
-                    none_vec = vec![None; n];
-                    statements.push(RangeStatement::init(
-                        RANGE_PARAMETERS[power as usize].to_owned(), padded_commitments, none_vec, None
-                    )?);
-                }
-                //extract TariRangeProofs from BulletPlusRangeProof
-                _proofs = proof_group.iter().map(|proof| proof.0.to_owned()).collect();
+                let (statements, _proofs) = groups.entry(bit_range).or_default();
+                statements.push(get_statement(coms, mins, bit_range, None)?);
+                _proofs.push(proof.proof);
+            }
 
-                match TariRangeProof::verify_batch(
-                    TRANSCRIPT_LABEL, &statements, &_proofs, VerifyAction::VerifyOnly
+            for (statements, _proofs) in groups.values() {
+                //Split the proofs and commitments into smaller batches
+                //Tari's BP+ implementation limits batch sizes to 256
+                //This is a way to get around that
+                for (statement_group, proof_group) in zip(
+                    statements.chunks(MAX_BATCH_GROUP_SIZE), _proofs.chunks(MAX_BATCH_GROUP_SIZE)
                 ) {
-                    //continue to the next group if valid
-                    Ok(_) => (),
-                    Err(e) => return Err(e)
-                };
+                    match TariRangeProof::verify_batch(
+                        TRANSCRIPT_LABEL, statement_group, proof_group, VerifyAction::VerifyOnly
+                    ) {
+                        //continue to the next group if valid
+                        Ok(_) => (),
+                        Err(e) => return Err(e)
+                    };
+                }
             }
             //if no group returned an error, then the batch is valid
             return Ok(());
         }
 
-        if commitments.len() != proofs.len() {
+        if commitments.len() != proofs.len() || commitments.len() != bit_ranges.len() {
             return Err(RangeProofError::Malformed)
         }
+        for (bit_range, proof) in zip(&bit_ranges, &proofs) {
+            check_bit_range(*bit_range)?;
+            if proof.bit_range != *bit_range {
+                return Err(RangeProofError::Malformed)
+            }
+        }
 
         //check maximum aggregation size
         for commitment_group in &commitments {
@@ -227,7 +421,107 @@ pub struct BulletPlusRangeProof (
             }
         }
 
-        match inner(commitments, proofs) {
+        match inner(commitments, minimums, bit_ranges, proofs) {
+            Ok(result) => Ok(result),
+            Err(e) => match e {
+                TariProofError::VerificationFailed(_) => Err(RangeProofError::Invalid),
+                _ => Err(RangeProofError::Unspecified("failed to verify rangeproof".to_string()))
+            }
+        }
+    }
+
+    ///Verify a (non-aggregated) Bulletproofs+ rangeproof created by `prove_with_seed`,
+    ///and recover its blinding factor given the seed nonce.
+    ///
+    ///Returns the blinding factor if the proof is valid,
+    ///or `Err(RangeProofError)` if it's invalid.
+    ///
+    ///Note that an incorrect seed nonce will not cause an error, but will return an incorrect blinding factor.
+    ///If the value is known, the blinding factor can be checked with `Commitment::commit(value, blinding) == commitment`.
+    pub fn recover_blinding(commitment: Commitment, proof: BulletPlusRangeProof, seed_nonce: Scalar
+    ) -> Result<Scalar, RangeProofError> {
+
+        //wrapped so we don't have to deal wtih TariProofError
+        fn inner(commitment: Commitment, proof: BulletPlusRangeProof, seed_nonce: Scalar
+        ) -> Result<Option<Scalar>, TariProofError> {
+            let statement = get_statement(&[commitment], &[None], BIT_RANGE, Some(seed_nonce))?;
+            let masks = TariRangeProof::verify_batch(
+                TRANSCRIPT_LABEL, &[statement], &[proof.proof], VerifyAction::RecoverAndVerify
+            )?;
+            return match masks.first() {
+                Some(Some(mask)) => Ok(mask.blindings()?.first().copied()),
+                _ => Ok(None)
+            }
+        }
+
+        if proof.bit_range != BIT_RANGE {
+            return Err(RangeProofError::Malformed)
+        }
+        return match inner(commitment, proof, seed_nonce) {
+            Ok(Some(blinding)) => Ok(blinding),
+            Ok(None) => Err(RangeProofError::Unspecified("failed to recover blinding factor".to_string())),
+            Err(e) => match e {
+                TariProofError::VerificationFailed(_) => Err(RangeProofError::Invalid),
+                _ => Err(RangeProofError::Unspecified("failed to verify rangeproof".to_string()))
+            }
+        }
+    }
+
+    ///Create a (non-aggregated) Bulletproofs+ rangeproof for an asset commitment,
+    ///given a value, a blinding factor, and an asset tag.
+    ///
+    ///The commitment is `(v * T) + (b * G)`, see `AssetTag::commit`.
+    ///Since every asset tag is a different generator, these proofs can't be aggregated or batch-verified.
+    ///
+    ///Return a commitment and a BP+ rangeproof if proving was successful,
+    ///or `RangeProofError` if an error occurred.
+    pub fn prove_with_asset_tag(value: u64, blinding: Scalar, asset_tag: &AssetTag
+    ) -> Result<(Commitment, Self), RangeProofError> {
+
+        //wrapped so we don't have to deal wtih TariProofError
+        fn inner(value: u64, blinding: Scalar, asset_tag: &AssetTag
+        ) -> Result<(Commitment, BulletPlusRangeProof), TariProofError> {
+            let commitment = asset_tag.commit(value, blinding);
+            let statement = get_asset_statement(commitment, asset_tag)?;
+            let witness = RangeWitness::init(vec!(CommitmentOpening::new(value, vec!(blinding))))?;
+
+            let proof = TariRangeProof::prove(
+                TRANSCRIPT_LABEL, &statement, &witness
+            )?;
+
+            return Ok((commitment, BulletPlusRangeProof{proof, bit_range: BIT_RANGE}))
+        }
+
+        return match inner(value, blinding, asset_tag) {
+            Ok(proof) => Ok(proof),
+            Err(_) => Err(
+                RangeProofError::Unspecified("failed to create rangeproof".to_string())
+            )
+        }
+    }
+
+    ///Verify a Bulletproofs+ rangeproof created by `prove_with_asset_tag`,
+    ///given its associated asset commitment and asset tag.
+    ///
+    ///Returns `Ok()` if the proof is valid,
+    ///or `Err(RangeProofError)` if it's invalid.
+    pub fn verify_with_asset_tag(commitment: Commitment, asset_tag: &AssetTag, proof: BulletPlusRangeProof
+    ) -> Result<(), RangeProofError> {
+
+        //wrapped so we don't have to deal wtih TariProofError
+        fn inner(commitment: Commitment, asset_tag: &AssetTag, proof: BulletPlusRangeProof
+        ) -> Result<(), TariProofError> {
+            let statement = get_asset_statement(commitment, asset_tag)?;
+            TariRangeProof::verify_batch(
+                TRANSCRIPT_LABEL, &[statement], &[proof.proof], VerifyAction::VerifyOnly
+            )?;
+            return Ok(())
+        }
+
+        if proof.bit_range != BIT_RANGE {
+            return Err(RangeProofError::Malformed)
+        }
+        return match inner(commitment, asset_tag, proof) {
             Ok(result) => Ok(result),
             Err(e) => match e {
                 TariProofError::VerificationFailed(_) => Err(RangeProofError::Invalid),
@@ -238,12 +532,20 @@ pub struct BulletPlusRangeProof (
 
 } impl ToBytes<'_> for BulletPlusRangeProof {
     //TariRangeProof has its own encoding system so we don't need bincode
+    //the bit range is encoded as the first byte
     fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
-        return Ok(self.0.to_bytes());
+        return Ok([vec!(self.bit_range as u8), self.proof.to_bytes()].concat());
     }
     fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        let (bit_range, bytes) = match bytes.split_first() {
+            Some((bit_range, bytes)) => (*bit_range as usize, bytes),
+            None => return Err(SerializationError::DecodingError)
+        };
+        if check_bit_range(bit_range).is_err() {
+            return Err(SerializationError::DecodingError)
+        }
         return match TariRangeProof::from_bytes(bytes) {
-            Ok(proof) => Ok(Self(proof)),
+            Ok(proof) => Ok(Self{proof, bit_range}),
             Err(_) => Err(SerializationError::DecodingError)
         };
     }
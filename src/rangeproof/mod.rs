@@ -20,6 +20,8 @@ pub mod bulletplus_internal {
 }
 
 ///Commitment values (in atomic units) are allowed to be between 0 and 2<sup>`BIT_RANGE`</sup> - 1.
+///
+///Bulletproofs+ also support smaller bit ranges, see `BulletPlusRangeProof::prove_with_bit_range`.
 //This should not exceed 64, and must be a power of 2 when using Bulletproofs+.
 //Increasing this number will increase the size and verification time of rangeproofs.
 pub const BIT_RANGE: usize = 64;
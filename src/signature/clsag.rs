@@ -12,7 +12,7 @@ use super::signature_utils::*;
 const FILLER_SCALAR: Scalar = constants::BASEPOINT_ORDER;
 
 ///Create the signed message, including a hash of all keys.
-fn create_message(
+pub(super) fn create_message(
     encoded_ring_l: Vec<[u8; 32]>, encoded_ring_c: Vec<[u8; 32]>, pseudo_out: Commitment, key_image: RistrettoPoint, auxiliary_point: RistrettoPoint , msg: &[u8]
 ) -> [u8; 32] {
     let encoded_points = batch_encode_points(&vec!(pseudo_out.0, key_image, auxiliary_point));
@@ -20,6 +20,71 @@ fn create_message(
 }
 
 
+///Aggregation coefficients and aggregated public keys of a ring, used when signing.
+pub(super) struct AggregatedRing {
+    pub(super) linking_ac: Scalar,
+    pub(super) auxiliary_ac: Scalar,
+    w_left: Vec<RistrettoPoint>,
+    w_right: RistrettoPoint,
+    key_image_points: Vec<RistrettoPoint>
+
+} impl AggregatedRing {
+    ///Aggregate the (shifted) ring, key image, and auxiliary point, given the signed message `m`.
+    pub(super) fn new(
+        m: &[u8], ring_l: &[RistrettoPoint], ring_c: &[RistrettoPoint], key_image_points: Vec<RistrettoPoint>,
+        key_image: RistrettoPoint, auxiliary_point: RistrettoPoint
+    ) -> Self {
+        //create aggregation coefficients
+        let linking_ac = domain_h_scalar(m, domains::CLSAG_LINKING);
+        let auxiliary_ac = domain_h_scalar(m, domains::CLSAG_AUXILIARY);
+        //create aggregated public keys
+        let mut w_left: Vec<RistrettoPoint> = Vec::new();
+        for x in 0..ring_l.len() { w_left.push(
+            //(linking_ac * ring_l[x]) + (auxiliary_ac * ring_c[x])
+            RistrettoPoint::multiscalar_mul(
+                vec!(linking_ac, auxiliary_ac), vec!(ring_l[x], ring_c[x]))
+        ); }
+        //(linking_ac * key_image) + (auxiliary_ac * auxiliary_point)
+        let w_right = RistrettoPoint::multiscalar_mul(
+            vec!(linking_ac, auxiliary_ac), vec!(key_image, auxiliary_point)
+        );
+        return Self{linking_ac, auxiliary_ac, w_left, w_right, key_image_points}
+    }
+
+    ///Travel around the ring, starting after the signer's index `j`,
+    ///where `left` and `right` are the signer's nonce points.
+    ///
+    ///Returns `(c_0, c_j)`.
+    pub(super) fn complete_ring(
+        &self, m: &[u8], s: &[Scalar], j: usize, mut left: RistrettoPoint, mut right: RistrettoPoint
+    ) -> (Scalar, Scalar) {
+        let n = s.len();
+        let mut c_i = Scalar::one();
+        let mut c_0 = c_i;
+        let mut i = j;
+        for _ in 0..n {
+            i = (i + 1) % n;
+
+            c_i = domain_h_scalar(&[
+                m, &batch_encode_points(&vec!(left, right)).concat()
+            ].concat(), domains::CLSAG_COMMITMENT);
+
+            if i == 0 { c_0 = c_i }
+            if i == j { break }
+
+            left = (&s[i] * G) + (c_i * self.w_left[i]);
+
+            //(s[i] * key_image_points[i]) + (c[i] * w_right)
+            right = RistrettoPoint::multiscalar_mul(
+                vec!(s[i], c_i), vec!(self.key_image_points[i], self.w_right)
+            );
+
+        }
+        return (c_0, c_i)
+    }
+}
+
+
 ///Internal components of a CLSAG signature
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CLSAGSignatureInternal {
@@ -88,6 +153,28 @@ pub struct CLSAGSignature {
         )
     }
 
+    ///Sign several inputs at once, given **sorted** rings, the private keys of one enote in each ring,
+    ///the blinding factors of the outputs, and a message.
+    ///
+    ///The pseudo-out blinding factors are chosen automatically, such that the pseudo-outs are balanced with the outputs.
+    ///
+    ///Return the input commitments (aka "pseudo-outs") and CLSAG signatures, in the same order as `inputs`,
+    ///or `SignatureError` if an error occurred.
+    pub fn sign_multiple(
+        inputs: Vec<(Ring, EnoteKeys)>, output_blindings: &[Scalar], msg: &[u8]
+    ) -> Result<(Vec<Commitment>, Vec<Self>), SignatureError> {
+        let blindings = balanced_blindings(inputs.len(), output_blindings);
+
+        let mut pseudo_outs: Vec<Commitment> = Vec::new();
+        let mut signatures: Vec<Self> = Vec::new();
+        for ((ring, enote_keys), blinding) in inputs.into_iter().zip(blindings) {
+            let (pseudo_out, signature) = Self::sign(&ring, enote_keys, blinding, msg)?;
+            pseudo_outs.push(pseudo_out);
+            signatures.push(signature);
+        }
+        return Ok((pseudo_outs, signatures))
+    }
+
     ///Internal signing function.
     fn sign_internal(
         //So many parameters :(
@@ -131,50 +218,17 @@ pub struct CLSAGSignature {
             s.push(last_scalar);
         }
 
-        //create aggregation coefficients
-        let linking_ac = domain_h_scalar(&m, domains::CLSAG_LINKING);
-        let auxiliary_ac = domain_h_scalar(&m, domains::CLSAG_AUXILIARY);
-        //create aggregated public keys
-        let mut w_left: Vec<RistrettoPoint> = Vec::new();
-        for x in 0..n { w_left.push(
-            //(linking_ac * ring_l[x]) + (auxiliary_ac * ring_c[x])
-            RistrettoPoint::multiscalar_mul(
-                vec!(linking_ac, auxiliary_ac), vec!(ring_l[x], ring_c[x]))
-        ); }
-        //(linking_ac * key_image) + (auxiliary_ac * auxiliary_point)
-        let w_right = RistrettoPoint::multiscalar_mul(
-            vec!(linking_ac, auxiliary_ac), vec!(key_image, auxiliary_point)
-        );
-
-        //create aggregated secret key
-        let w_secret = (linking_ac * enote_keys.owner) + (auxiliary_ac * commitment_key);
-
         //compute starting values
-        let mut left = &s[j] * G;
-        let mut right = s[j] * key_image_points[j];
+        let left = &s[j] * G;
+        let right = s[j] * key_image_points[j];
 
-        let mut c_i = Scalar::one();
-        let mut c_0 = c_i;
-        let mut i = j;
-        for _ in 0..n {
-            i = (i + 1) % n;
-
-            c_i = domain_h_scalar(&[
-                m, &batch_encode_points(&vec!(left, right)).concat()
-            ].concat(), domains::CLSAG_COMMITMENT);
-
-            if i == 0 { c_0 = c_i }
-            if i == j { break }
+        let aggregated_ring = AggregatedRing::new(
+            m, &ring_l, &ring_c, key_image_points, key_image, auxiliary_point);
+        let (c_0, c_j) = aggregated_ring.complete_ring(m, &s, j, left, right);
 
-            left = (&s[i] * G) + (c_i * w_left[i]);
-
-            //(s[i] * key_image_points[i]) + (c[i] * w_right)
-            right = RistrettoPoint::multiscalar_mul(
-                vec!(s[i], c_i), vec!(key_image_points[i], w_right)
-            );
-
-        }
-        s[j] -= c_i * w_secret;
+        //create aggregated secret key
+        let w_secret = (aggregated_ring.linking_ac * enote_keys.owner) + (aggregated_ring.auxiliary_ac * commitment_key);
+        s[j] -= c_j * w_secret;
 
         seed.zeroize();
         commitment_key.zeroize();
@@ -326,6 +380,11 @@ pub struct CLSAGSignature {
         )
     }
 
+    ///Create a signature from its components.
+    pub(super) fn from_parts(key_image: RistrettoPoint, c_0: Scalar, s: Vec<Scalar>, auxiliary: RistrettoPoint) -> Self {
+        return Self{key_image, c_0, s, auxiliary}
+    }
+
     ///Combine separate proof components back together.
     pub fn from_separate(key_image: RistrettoPoint, internal: CLSAGSignatureInternal) -> CLSAGSignature {
         CLSAGSignature{
@@ -0,0 +1,148 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use zeroize::Zeroize;
+
+use crate::internal_common::*;
+use super::signature_utils::*;
+
+///Return the key image point of each encoded public key, for the given context: `Hp(context, P)`.
+fn get_context_key_image_points(encoded_keys: &[[u8; 32]], context: &[u8]) -> Vec<RistrettoPoint> {
+    let context = h_bytes(context);
+    return encoded_keys.iter()
+        .map(|key| domain_h_point(&[context.as_slice(), key].concat(), domains::LSAG_KEY_IMAGE))
+        .collect()
+}
+
+///Create the signed message, including a hash of all keys, the key image, and the context.
+fn create_message(encoded_keys: &[[u8; 32]], key_image: RistrettoPoint, context: &[u8], msg: &[u8]) -> [u8; 32] {
+    return h_bytes(&[
+        msg, &h_bytes(context), &encoded_keys.concat(), &encode_point(&key_image)
+    ].concat())
+}
+
+
+///A linkable ring signature over a ring of public keys, without commitments.
+///
+///LSAG stands for "Linkable Spontaneous Anonymous Group (signature)"
+///
+///Unlike `CLSAGSignature`, the key image is derived from an application-supplied context (such as a poll ID):
+///two signatures by the same key with the same context have the same key image,
+///but key images of different contexts can't be linked to each other, or to the standard key image used for spending.
+///This allows, for example, anonymous polls where each member of the ring can only vote once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LSAGSignature {
+    key_image: RistrettoPoint,
+    c_0: Scalar,
+    s: Vec<Scalar>
+
+} impl LSAGSignature {
+    ///Create a signature given a **sorted** ring of public keys (see `sort_public_keys`),
+    ///the private key of one of them, a context, and a message.
+    ///
+    ///Return an LSAG signature if signing was successful,
+    ///or `SignatureError` if an error occurred.
+    pub fn sign(ring: &[RistrettoPoint], private_key: &Scalar, context: &[u8], msg: &[u8]) -> Result<Self, SignatureError> {
+        let encoded_keys = batch_encode_points(&ring.to_vec());
+        if !keys_are_sorted(&encoded_keys) {
+            return Err(SignatureError::UnsortedRing)
+        }
+
+        //find the signer's key in the ring
+        let j = match ring.iter().position(|key| key == &(private_key * G)) {
+            Some(key_index) => key_index,
+            None => return Err(SignatureError::EnoteNotInRing)
+        };
+        let n = ring.len();
+
+        let key_image_points = get_context_key_image_points(&encoded_keys, context);
+        let key_image = private_key * key_image_points[j];
+
+        let m = create_message(&encoded_keys, key_image, context, msg);
+        let m = m.as_slice();
+
+        let mut s: Vec<Scalar> = (0..n).map(|_| Scalar::generate()).collect();
+
+        //compute starting values
+        let mut left = &s[j] * G;
+        let mut right = s[j] * key_image_points[j];
+
+        let mut c_i = Scalar::one();
+        let mut c_0 = c_i;
+        let mut i = j;
+        for _ in 0..n {
+            i = (i + 1) % n;
+
+            c_i = domain_h_scalar(&[
+                m, &batch_encode_points(&vec!(left, right)).concat()
+            ].concat(), domains::LSAG_COMMITMENT);
+
+            if i == 0 { c_0 = c_i }
+            if i == j { break }
+
+            left = (&s[i] * G) + (c_i * ring[i]);
+            //(s[i] * key_image_points[i]) + (c[i] * key_image)
+            right = RistrettoPoint::multiscalar_mul(
+                vec!(s[i], c_i), vec!(key_image_points[i], key_image)
+            );
+        }
+        let mut secret = c_i * private_key;
+        s[j] -= secret;
+
+        secret.zeroize();
+
+        return Ok(Self{key_image, c_0, s})
+    }
+
+    ///Given an LSAG signature, a **sorted** ring of public keys, a context, and a message, check if it's valid.
+    ///
+    ///Returns `Ok()` if the signature is valid,
+    ///or `Err(SignatureError)` if it's invalid or an error occurred.
+    pub fn verify(signature: &LSAGSignature, ring: &[RistrettoPoint], context: &[u8], msg: &[u8]) -> Result<(), SignatureError> {
+        let n = ring.len();
+        if n == 0 || signature.s.len() != n {
+            return Err(SignatureError::Malformed)
+        }
+
+        let encoded_keys = batch_encode_points(&ring.to_vec());
+        if !keys_are_sorted(&encoded_keys) {
+            return Err(SignatureError::UnsortedRing)
+        }
+
+        let key_image_points = get_context_key_image_points(&encoded_keys, context);
+        let m = create_message(&encoded_keys, signature.key_image, context, msg);
+        let m = m.as_slice();
+
+        //travel around the ring
+        let mut c_i = signature.c_0;
+        for i in 0..n {
+            //(s[i] * G) + (c[i] * ring[i])
+            let left = G_MULTISCALAR_MUL.vartime_mixed_multiscalar_mul(
+                vec!(signature.s[i]), vec!(c_i), vec!(ring[i])
+            );
+
+            //(s[i] * key_image_points[i]) + (c[i] * key_image)
+            let right = RistrettoPoint::vartime_multiscalar_mul(
+                vec!(signature.s[i], c_i), vec!(key_image_points[i], signature.key_image)
+            );
+
+            c_i = domain_h_scalar(&[
+                m, &batch_encode_points(&vec!(left, right)).concat()
+            ].concat(), domains::LSAG_COMMITMENT);
+        }
+        //check if we end up back where we started
+        return match c_i == signature.c_0 {
+            true => Ok(()),
+            false => Err(SignatureError::Invalid)
+        };
+    }
+
+    ///Return the key image of this signature, which is the same for all signatures by the same key with the same context.
+    pub fn get_key_image(&self) -> RistrettoPoint {
+        return self.key_image
+    }
+
+} impl ToBytes<'_> for LSAGSignature {}
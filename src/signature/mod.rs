@@ -18,15 +18,27 @@
 
 mod mlsag;
 mod clsag;
+mod lsag;
+mod sag;
 mod signature_utils;
 
+pub mod multisig;
+pub mod threshold;
+
 pub use mlsag::{MLSAGSignature, MLSAGSignatureInternal};
 pub use clsag::{CLSAGSignature, CLSAGSignatureInternal};
+pub use lsag::LSAGSignature;
+pub use sag::SAGSignature;
+pub use signature_utils::{
+    balanced_blindings,
+    sort_public_keys
+};
 
 pub(crate) use signature_utils::{
     separate_ring,
     encode_rings,
     ring_as_sorted,
     ring_is_sorted,
-    get_key_image
+    get_key_image,
+    key_image_point
 };
\ No newline at end of file
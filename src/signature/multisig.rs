@@ -0,0 +1,313 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! N-of-N multisignature CLSAG signing.
+//!
+//! Several parties can jointly own an enote, without any of them knowing its full owner private key,
+//! and together create a standard `CLSAGSignature` which is verified with `CLSAGSignature::verify`.
+//!
+//! 1. Key aggregation: each party shares its public key,
+//!    then creates a `MultisigKeyShare` from its private key and all public keys.
+//!    The shared owner public key is `aggregate_public_keys(public_keys)`.
+//! 2. Key image: each party shares its `partial_key_image`, which sum to the standard key image.
+//! 3. Nonce commitment: each party creates a `MultisigNonce`, and shares the commitment of its public nonce.
+//! 4. Nonce reveal: once all commitments are received, each party shares its public nonce,
+//!    and checks the other parties' public nonces against their commitments.
+//! 5. One party creates a `MultisigContext` from the ring, partial key images, and public nonces,
+//!    and shares it with the other parties.
+//! 6. Each party creates a partial response with `partial_sign`,
+//!    and any party can `combine` them into the final signature.
+//!
+//! The context includes the commitment key (the difference between the enote's blinding factor and the pseudo-out's),
+//! so it should only be shared between the signers.
+//! The signers are assumed to all know the enote's value and blinding factor.
+
+use zeroize::Zeroize;
+
+use crate::internal_common::*;
+use super::signature_utils::*;
+use super::clsag::{
+    CLSAGSignature,
+    AggregatedRing,
+    create_message
+};
+
+///Return the hash of a set of public keys, independent of their order.
+fn hash_public_keys(public_keys: &[RistrettoPoint]) -> [u8; 32] {
+    let mut encoded = batch_encode_points(&public_keys.to_vec());
+    encoded.sort_unstable();
+    return h_bytes(&encoded.concat())
+}
+
+///Return the aggregation coefficient of a public key, given the hash of all public keys.
+fn key_coefficient(keys_hash: &[u8; 32], public_key: &RistrettoPoint) -> Scalar {
+    return domain_h_scalar(
+        &[keys_hash.as_slice(), &encode_point(public_key)].concat(), domains::MULTISIG_KEY_AGGREGATION)
+}
+
+///Aggregate several public keys into a shared public key.
+///
+///Each key is weighted by a coefficient which depends on all keys, to prevent rogue key attacks.
+///The order of the keys does not matter.
+///
+///Returns `SignatureError::Malformed` if the list is empty or contains duplicates.
+pub fn aggregate_public_keys(public_keys: &[RistrettoPoint]) -> Result<RistrettoPoint, SignatureError> {
+    check_public_keys(public_keys)?;
+    let keys_hash = hash_public_keys(public_keys);
+    return Ok(RistrettoPoint::multiscalar_mul(
+        public_keys.iter().map(|key| key_coefficient(&keys_hash, key)), public_keys
+    ))
+}
+
+///Check that a set of public keys is not empty, and has no duplicates.
+fn check_public_keys(public_keys: &[RistrettoPoint]) -> Result<(), SignatureError> {
+    let mut encoded = batch_encode_points(&public_keys.to_vec());
+    encoded.sort_unstable();
+    encoded.dedup();
+    if encoded.is_empty() || encoded.len() != public_keys.len() {
+        return Err(SignatureError::Malformed)
+    }
+    return Ok(())
+}
+
+
+///One party's share of a jointly owned private key.
+///
+///The shares of all signers sum to the shared private key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultisigKeyShare {
+    share: Scalar,
+    public_key: RistrettoPoint
+
+} impl MultisigKeyShare {
+    ///Create a key share given this party's private key, and the public keys of all parties (including this one).
+    ///
+    ///Returns `SignatureError::Malformed` if this party's public key is not in the list,
+    ///or if the list contains duplicates.
+    pub fn new(private_key: Scalar, public_keys: &[RistrettoPoint]) -> Result<Self, SignatureError> {
+        let own_public_key = &private_key * G;
+        if !public_keys.contains(&own_public_key) {
+            return Err(SignatureError::Malformed)
+        }
+        let public_key = aggregate_public_keys(public_keys)?;
+        let coefficient = key_coefficient(&hash_public_keys(public_keys), &own_public_key);
+        return Ok(Self{share: coefficient * private_key, public_key})
+    }
+
+    ///Create a key share from an already weighted share of the private key.
+    pub(crate) fn from_weighted_share(share: Scalar, public_key: RistrettoPoint) -> Self {
+        return Self{share, public_key}
+    }
+
+    ///Return the shared public key, which owns the enote.
+    pub fn public_key(&self) -> RistrettoPoint {
+        return self.public_key
+    }
+
+    ///Return this party's share of the key image.
+    ///
+    ///The partial key images of all parties sum to the standard key image of the shared key.
+    pub fn partial_key_image(&self) -> RistrettoPoint {
+        return self.share * key_image_point(&self.public_key)
+    }
+
+    ///Create this party's partial response, given its nonce, the shared signing context, and the message.
+    ///
+    ///The nonce is consumed, and must never be used again.
+    ///
+    ///Returns `SignatureError::Malformed` if the context is malformed, or not for this key.
+    pub fn partial_sign(&self, nonce: MultisigNonce, context: &MultisigContext, msg: &[u8]) -> Result<Scalar, SignatureError> {
+        context.check()?;
+        if context.ring.0[context.index].owner != self.public_key {
+            return Err(SignatureError::Malformed)
+        }
+        let (_, c_j, aggregated_ring) = context.challenge(msg);
+        return Ok(nonce.0 - (c_j * aggregated_ring.linking_ac * self.share))
+    }
+
+} impl Zeroize for MultisigKeyShare {
+    fn zeroize(&mut self) {
+        self.share.zeroize();
+    }
+
+} impl Drop for MultisigKeyShare {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+
+} impl ToBytes<'_> for MultisigKeyShare {}
+
+
+///A secret, single-use signing nonce.
+#[derive(Debug, Zeroize)]
+pub struct MultisigNonce(
+    Scalar
+
+); impl MultisigNonce {
+    ///Generate a new nonce for signing with `key_share`.
+    ///
+    ///Returns the secret nonce, and the public nonce to share with the other parties.
+    pub fn generate(key_share: &MultisigKeyShare) -> (Self, MultisigNoncePublic) {
+        let nonce = Scalar::generate();
+        let public = MultisigNoncePublic{
+            left: &nonce * G,
+            right: nonce * key_image_point(&key_share.public_key)
+        };
+        return (Self(nonce), public)
+    }
+
+} impl Drop for MultisigNonce {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+///A public signing nonce.
+///
+///Its commitment must be shared (and received from all other parties) before it is revealed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MultisigNoncePublic {
+    left: RistrettoPoint,
+    right: RistrettoPoint
+
+} impl MultisigNoncePublic {
+    ///Return the commitment to this public nonce.
+    pub fn commitment(&self) -> [u8; 32] {
+        return domain_h_bytes(
+            &batch_encode_points(&vec!(self.left, self.right)).concat(), domains::MULTISIG_NONCE)
+    }
+
+    ///Check that this public nonce matches a previously received commitment.
+    pub fn verify_commitment(&self, commitment: &[u8; 32]) -> Result<(), SignatureError> {
+        return match &self.commitment() == commitment {
+            true => Ok(()),
+            false => Err(SignatureError::Invalid)
+        }
+    }
+
+} impl ToBytes<'_> for MultisigNoncePublic {}
+
+
+///The shared state of a multisig signing session.
+///
+///This includes the commitment key, so it should only be shared between the signers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultisigContext {
+    ring: Ring,
+    index: usize,
+    pseudo_out: Commitment,
+    commitment_key: Scalar,
+    key_image: RistrettoPoint,
+    auxiliary: RistrettoPoint,
+    nonce_left: RistrettoPoint,
+    nonce_right: RistrettoPoint,
+    s: Vec<Scalar>
+
+} impl MultisigContext {
+    ///Create a signing context given a **sorted** ring, the shared public key, the enote's value and blinding factor,
+    ///a new blinding factor for the pseudo-out, and the partial key images and public nonces of all parties.
+    ///
+    ///Returns the context to share with all signers,
+    ///or `SignatureError` if an error occurred.
+    pub fn new(
+        ring: &Ring, public_key: RistrettoPoint, value: u64, blinding: Scalar, pseudo_out_blinding: Scalar,
+        partial_key_images: &[RistrettoPoint], nonces: &[MultisigNoncePublic]
+    ) -> Result<Self, SignatureError> {
+        let [ring_l, ring_c] = separate_ring(ring);
+        let (encoded_ring_l, encoded_ring_c) = encode_rings(ring_l, ring_c);
+        if !ring_is_sorted(ring, &encoded_ring_l, &encoded_ring_c) {
+            return Err(SignatureError::UnsortedRing);
+        }
+        if partial_key_images.is_empty() || partial_key_images.len() != nonces.len() {
+            return Err(SignatureError::Malformed)
+        }
+
+        //find the enote in the ring
+        let enote = Enote::new(public_key, Commitment::commit(value, blinding));
+        let index = match ring.0.iter().position(|ring_enote| ring_enote == &enote) {
+            Some(key_index) => key_index,
+            None => return Err(SignatureError::EnoteNotInRing)
+        };
+
+        let commitment_key = blinding - pseudo_out_blinding;
+        let auxiliary = commitment_key * h_key_image_point(&encoded_ring_l[index]);
+
+        return Ok(Self{
+            ring: ring.to_owned(),
+            index,
+            pseudo_out: Commitment::commit(value, pseudo_out_blinding),
+            commitment_key,
+            key_image: partial_key_images.iter().sum(),
+            auxiliary,
+            nonce_left: nonces.iter().map(|nonce| nonce.left).sum(),
+            nonce_right: nonces.iter().map(|nonce| nonce.right).sum(),
+            //the signer's response is filled in when combining
+            s: (0..ring.0.len()).map(|_| Scalar::generate()).collect()
+        })
+    }
+
+    ///Return the input commitment (aka "pseudo-out").
+    pub fn pseudo_out(&self) -> Commitment {
+        return self.pseudo_out
+    }
+
+    ///Return the key image.
+    pub fn key_image(&self) -> RistrettoPoint {
+        return self.key_image
+    }
+
+    ///Check that the signer's index and the responses match the ring,
+    ///since the context may be received from another party.
+    fn check(&self) -> Result<(), SignatureError> {
+        if self.index >= self.ring.0.len() || self.s.len() != self.ring.0.len() {
+            return Err(SignatureError::Malformed)
+        }
+        return Ok(())
+    }
+
+    ///Travel around the ring, starting from the joint nonce.
+    ///
+    ///Returns `(c_0, c_j, aggregated_ring)`.
+    fn challenge(&self, msg: &[u8]) -> (Scalar, Scalar, AggregatedRing) {
+        let [ring_l, unshifted_ring_c] = separate_ring(&self.ring);
+        let (encoded_ring_l, encoded_ring_c) = encode_rings(ring_l.clone(), unshifted_ring_c.clone());
+        let ring_c = shift_commitments(&unshifted_ring_c, self.pseudo_out);
+        let key_image_points = get_key_image_points(&encoded_ring_l);
+
+        let m = create_message(encoded_ring_l, encoded_ring_c, self.pseudo_out, self.key_image, self.auxiliary, msg);
+        let m = m.as_slice();
+
+        let aggregated_ring = AggregatedRing::new(
+            m, &ring_l, &ring_c, key_image_points, self.key_image, self.auxiliary);
+        let (c_0, c_j) = aggregated_ring.complete_ring(
+            m, &self.s, self.index, self.nonce_left, self.nonce_right);
+        return (c_0, c_j, aggregated_ring)
+    }
+
+    ///Combine the partial responses of all parties into the final signature.
+    ///
+    ///Returns the input commitment (aka "pseudo-out") and a CLSAG signature if successful,
+    ///or `SignatureError::Invalid` if any party's partial response (or partial key image) was incorrect,
+    ///or `SignatureError::Malformed` if the context is malformed.
+    pub fn combine(&self, partial_responses: &[Scalar], msg: &[u8]) -> Result<(Commitment, CLSAGSignature), SignatureError> {
+        self.check()?;
+        let (c_0, c_j, aggregated_ring) = self.challenge(msg);
+
+        let mut s = self.s.to_owned();
+        s[self.index] = partial_responses.iter().sum::<Scalar>()
+            - (c_j * aggregated_ring.auxiliary_ac * self.commitment_key);
+
+        let signature = CLSAGSignature::from_parts(self.key_image, c_0, s, self.auxiliary);
+        CLSAGSignature::verify(signature.clone(), &self.ring, self.pseudo_out, msg)?;
+        return Ok((self.pseudo_out, signature))
+    }
+
+} impl Drop for MultisigContext {
+    fn drop(&mut self) {
+        self.commitment_key.zeroize()
+    }
+
+} impl ToBytes<'_> for MultisigContext {}
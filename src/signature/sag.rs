@@ -0,0 +1,143 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use zeroize::Zeroize;
+
+use crate::internal_common::*;
+use crate::address::cryptonote::{
+    CryptoNotePrivate,
+    CryptoNotePublic
+};
+use super::signature_utils::*;
+
+///Create the signed message, including a hash of all keys.
+fn create_message(encoded_keys: &[[u8; 32]], msg: &[u8]) -> [u8; 32] {
+    return h_bytes(&[msg, &encoded_keys.concat()].concat())
+}
+
+///Return the sorted spend keys of CryptoNote addresses, without duplicates.
+fn get_spend_keys(ring: &[CryptoNotePublic]) -> Vec<RistrettoPoint> {
+    let keys: Vec<RistrettoPoint> = ring.iter().map(|address| address.spend).collect();
+    return sort_public_keys(&keys)
+}
+
+
+///A (non-linkable) ring signature over a ring of public keys, for signing arbitrary messages.
+///
+///SAG stands for "Spontaneous Anonymous Group (signature)", also known as an AOS ring signature.
+///
+///This only proves that one of the ring's keys signed the message, without revealing which one.
+///There is no key image, so signatures by the same key can't be linked (see `LSAGSignature` for linkable signatures).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SAGSignature {
+    c_0: Scalar,
+    s: Vec<Scalar>
+
+} impl SAGSignature {
+    ///Create a signature given a **sorted** ring of public keys (see `sort_public_keys`),
+    ///the private key of one of them, and a message.
+    ///
+    ///Return a SAG signature if signing was successful,
+    ///or `SignatureError` if an error occurred.
+    pub fn sign(ring: &[RistrettoPoint], private_key: &Scalar, msg: &[u8]) -> Result<Self, SignatureError> {
+        let encoded_keys = batch_encode_points(&ring.to_vec());
+        if !keys_are_sorted(&encoded_keys) {
+            return Err(SignatureError::UnsortedRing)
+        }
+
+        //find the signer's key in the ring
+        let j = match ring.iter().position(|key| key == &(private_key * G)) {
+            Some(key_index) => key_index,
+            None => return Err(SignatureError::EnoteNotInRing)
+        };
+        let n = ring.len();
+
+        let m = create_message(&encoded_keys, msg);
+        let m = m.as_slice();
+
+        let mut s: Vec<Scalar> = (0..n).map(|_| Scalar::generate()).collect();
+
+        //compute starting value
+        let mut left = &s[j] * G;
+
+        let mut c_i = Scalar::one();
+        let mut c_0 = c_i;
+        let mut i = j;
+        for _ in 0..n {
+            i = (i + 1) % n;
+
+            c_i = domain_h_scalar(&[
+                m, &encode_point(&left)
+            ].concat(), domains::SAG_COMMITMENT);
+
+            if i == 0 { c_0 = c_i }
+            if i == j { break }
+
+            left = (&s[i] * G) + (c_i * ring[i]);
+        }
+        let mut secret = c_i * private_key;
+        s[j] -= secret;
+
+        secret.zeroize();
+
+        return Ok(Self{c_0, s})
+    }
+
+    ///Create a signature given a ring of CryptoNote addresses, the private keys of one of them, and a message.
+    ///
+    ///The signature is made with the addresses' spend keys, which are sorted automatically,
+    ///so the ring of addresses can be in any order.
+    ///Signatures created by this function are verified with `verify_with_addresses`.
+    pub fn sign_with_addresses(ring: &[CryptoNotePublic], keys: &CryptoNotePrivate, msg: &[u8]) -> Result<Self, SignatureError> {
+        return Self::sign(&get_spend_keys(ring), &keys.spend, msg)
+    }
+
+    ///Given a SAG signature, a **sorted** ring of public keys, and a message, check if it's valid.
+    ///
+    ///Returns `Ok()` if the signature is valid,
+    ///or `Err(SignatureError)` if it's invalid or an error occurred.
+    pub fn verify(signature: &SAGSignature, ring: &[RistrettoPoint], msg: &[u8]) -> Result<(), SignatureError> {
+        let n = ring.len();
+        if n == 0 || signature.s.len() != n {
+            return Err(SignatureError::Malformed)
+        }
+
+        let encoded_keys = batch_encode_points(&ring.to_vec());
+        if !keys_are_sorted(&encoded_keys) {
+            return Err(SignatureError::UnsortedRing)
+        }
+
+        let m = create_message(&encoded_keys, msg);
+        let m = m.as_slice();
+
+        //travel around the ring
+        let mut c_i = signature.c_0;
+        for (s_i, key) in signature.s.iter().zip(ring) {
+            //(s[i] * G) + (c[i] * ring[i])
+            let left = G_MULTISCALAR_MUL.vartime_mixed_multiscalar_mul(
+                vec!(s_i), vec!(c_i), vec!(key)
+            );
+
+            c_i = domain_h_scalar(&[
+                m, &encode_point(&left)
+            ].concat(), domains::SAG_COMMITMENT);
+        }
+        //check if we end up back where we started
+        return match c_i == signature.c_0 {
+            true => Ok(()),
+            false => Err(SignatureError::Invalid)
+        };
+    }
+
+    ///Given a SAG signature, a ring of CryptoNote addresses (in any order), and a message, check if it's valid.
+    ///
+    ///Returns `Ok()` if the signature is valid,
+    ///or `Err(SignatureError)` if it's invalid or an error occurred.
+    pub fn verify_with_addresses(signature: &SAGSignature, ring: &[CryptoNotePublic], msg: &[u8]) -> Result<(), SignatureError> {
+        return Self::verify(signature, &get_spend_keys(ring), msg)
+    }
+
+} impl ToBytes<'_> for SAGSignature {}
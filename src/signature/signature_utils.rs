@@ -18,6 +18,11 @@ pub(crate) fn h_key_image_point(msg: &[u8]) -> RistrettoPoint {
     return domain_h_point(msg, domains::SIGNATURE_KEY_IMAGE);
 }
 
+///Return the key image point of a public key, `Hp(P)`.
+pub(crate) fn key_image_point(public_key: &RistrettoPoint) -> RistrettoPoint {
+    return h_key_image_point(&batch_encode_points(&vec!(*public_key))[0])
+}
+
 ///return the key image points for a vector of encoded public keys
 pub(crate) fn get_key_image_points(encoded_pubs: &Vec<[u8; 32]>) -> Vec<RistrettoPoint> {
     return encoded_pubs.into_iter()
@@ -87,4 +92,40 @@ pub(crate) fn ring_is_sorted(ring: &Ring, encoded_ring_l: &Vec<[u8; 32]>, encode
     //ensure that no values are duplicated or out of order
     return encoded_enotes.windows(2)
         .all(|enotes| enotes[0] < enotes[1])
+}
+
+///Return the public keys sorted, without duplicates
+pub(crate) fn keys_as_sorted(keys: &[RistrettoPoint], encoded_keys: &[[u8; 32]]) -> Vec<RistrettoPoint> {
+    let mut encoded: Vec<([u8; 32], RistrettoPoint)> = encoded_keys.iter().copied().zip(keys.iter().copied()).collect();
+    encoded.sort_unstable_by_key(|(encoded_key, _)| *encoded_key);
+    encoded.dedup_by_key(|(encoded_key, _)| *encoded_key);
+    return encoded.into_iter().map(|(_, key)| key).collect()
+}
+
+///Check if encoded public keys are sorted and have no duplicates
+pub(crate) fn keys_are_sorted(encoded_keys: &[[u8; 32]]) -> bool {
+    return encoded_keys.windows(2)
+        .all(|keys| keys[0] < keys[1])
+}
+
+///Sort a ring of public keys and remove duplicates, for use with `LSAGSignature` and `SAGSignature`.
+pub fn sort_public_keys(keys: &[RistrettoPoint]) -> Vec<RistrettoPoint> {
+    return keys_as_sorted(keys, &batch_encode_points(&keys.to_vec()))
+}
+
+///Generate random pseudo-out blinding factors for `n` inputs,
+///such that their sum is equal to the sum of `output_blindings`.
+///
+///Pseudo-outs created with these blinding factors will be balanced with the outputs
+///(see `Commitment::is_balanced`), as long as the values are also balanced.
+pub fn balanced_blindings(n: usize, output_blindings: &[Scalar]) -> Vec<Scalar> {
+    if n == 0 {
+        return Vec::new()
+    }
+    //all random, except for the last one
+    let mut blindings: Vec<Scalar> = (0..n - 1).map(|_| Scalar::generate()).collect();
+    blindings.push(
+        output_blindings.iter().sum::<Scalar>() - blindings.iter().sum::<Scalar>()
+    );
+    return blindings
 }
\ No newline at end of file
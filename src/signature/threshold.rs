@@ -0,0 +1,220 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! M-of-N threshold spending, using Shamir secret sharing.
+//!
+//! A dealer splits a private key into N shares, any M of which can sign together.
+//! The private key is never rebuilt: each signer weights its share with a Lagrange coefficient,
+//! and the weighted shares are used with the `multisig` signing protocol,
+//! which results in a standard `CLSAGSignature` and key image.
+//!
+//! For CryptoNote addresses, `ThresholdCryptoNote` splits both the view and spend keys.
+//! Any M participants can detect and decrypt incoming payments by combining their partial shared secrets,
+//! and then spend them.
+//!
+//! Note that the dealer knows the full private keys while dealing, and should delete them afterwards.
+
+use zeroize::Zeroize;
+
+use crate::internal_common::*;
+use crate::address::{
+    Recipient,
+    SharedSecret,
+    PaymentId,
+    cryptonote::{
+        CryptoNotePrivate,
+        CryptoNotePublic
+    }
+};
+use super::multisig::MultisigKeyShare;
+
+///Check the signer indices, and return the Lagrange coefficient of `index`.
+///
+///Participants are numbered from 1, so an index of 0 is rejected.
+fn lagrange_coefficient(index: u64, signers: &[u64], threshold: usize) -> Result<Scalar, SignatureError> {
+    let mut sorted = signers.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    if index == 0 || signers.contains(&0) {
+        return Err(SignatureError::Malformed)
+    }
+    if sorted.len() != signers.len() || signers.len() < threshold || !signers.contains(&index) {
+        return Err(SignatureError::Malformed)
+    }
+
+    //product of (x_j / (x_j - x_i)) for all other signers
+    let x_i = Scalar::from(index);
+    let mut numerator = Scalar::one();
+    let mut denominator = Scalar::one();
+    for x_j in signers.iter().filter(|signer| **signer != index).map(|signer| Scalar::from(*signer)) {
+        numerator *= x_j;
+        denominator *= x_j - x_i;
+    }
+    return Ok(numerator * denominator.invert())
+}
+
+
+///One participant's Shamir share of a private key.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThresholdShare {
+    index: u64,
+    threshold: usize,
+    share: Scalar
+
+} impl ThresholdShare {
+    ///Split `secret` into `participants` shares, any `threshold` of which can sign together.
+    ///
+    ///Participants are numbered from 1 to `participants`, see `index`.
+    ///
+    ///Returns `SignatureError::Malformed` if `threshold` is 0 or larger than `participants`.
+    pub fn deal(secret: &Scalar, threshold: usize, participants: usize) -> Result<Vec<Self>, SignatureError> {
+        if threshold == 0 || threshold > participants {
+            return Err(SignatureError::Malformed)
+        }
+
+        //random polynomial, where f(0) = secret
+        let mut coefficients: Vec<Scalar> = vec!(*secret);
+        coefficients.extend((1..threshold).map(|_| Scalar::generate()));
+
+        let mut shares: Vec<Self> = Vec::new();
+        for index in 1..=participants as u64 {
+            //evaluate f(index) using Horner's method
+            let x = Scalar::from(index);
+            let share = coefficients.iter().rev()
+                .fold(Scalar::zero(), |result, coefficient| (result * x) + coefficient);
+            shares.push(Self{index, threshold, share});
+        }
+        coefficients.zeroize();
+        return Ok(shares)
+    }
+
+    ///Return this participant's index.
+    pub fn index(&self) -> u64 {
+        return self.index
+    }
+
+    ///Return the number of participants needed to sign.
+    pub fn threshold(&self) -> usize {
+        return self.threshold
+    }
+
+    ///Return this participant's share of `secret + offset`.
+    ///
+    ///This can be used to turn a share of a spend key into a share of a one-time (enote) key.
+    pub fn add_offset(&self, offset: &Scalar) -> Self {
+        return Self{
+            index: self.index,
+            threshold: self.threshold,
+            share: self.share + offset
+        }
+    }
+
+    ///Create a multisig key share for signing with the participants in `signers`, which must include this participant.
+    ///
+    ///`public_key` is the public key of the shared private key (ie, the enote's owner).
+    ///The resulting key shares of all signers are then used with the `multisig` protocol.
+    ///
+    ///Returns `SignatureError::Malformed` if there are not enough signers, or duplicate signers.
+    pub fn to_key_share(&self, signers: &[u64], public_key: RistrettoPoint) -> Result<MultisigKeyShare, SignatureError> {
+        let coefficient = lagrange_coefficient(self.index, signers, self.threshold)?;
+        return Ok(MultisigKeyShare::from_weighted_share(coefficient * self.share, public_key))
+    }
+
+    ///Calculate this participant's part of a shared secret with `other_public`,
+    ///for the participants in `signers`, which must include this participant.
+    ///
+    ///The partial shared secrets of all signers can be combined with `combine_shared_secret`.
+    pub fn partial_shared_secret(&self, signers: &[u64], other_public: &RistrettoPoint) -> Result<RistrettoPoint, SignatureError> {
+        let coefficient = lagrange_coefficient(self.index, signers, self.threshold)?;
+        return Ok((coefficient * self.share) * other_public)
+    }
+
+} impl Zeroize for ThresholdShare {
+    fn zeroize(&mut self) {
+        self.share.zeroize();
+    }
+
+} impl Drop for ThresholdShare {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+
+} impl ToBytes<'_> for ThresholdShare {}
+
+///Combine the partial shared secrets of all signers into the shared secret.
+pub fn combine_shared_secret(partial_shared_secrets: &[RistrettoPoint]) -> SharedSecret {
+    return SharedSecret::from_point(&partial_shared_secrets.iter().sum())
+}
+
+
+///An enote received by a `ThresholdCryptoNote`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThresholdEnote {
+    ///This participant's share of the enote's owner private key
+    pub owner: ThresholdShare,
+    ///The enote's owner public key
+    pub public_key: RistrettoPoint,
+    pub value: u64,
+    pub blinding: Scalar,
+    ///The payment ID, if the payment was sent to an integrated address
+    pub payment_id: Option<PaymentId>
+
+} impl ToBytes<'_> for ThresholdEnote {}
+
+///One participant's share of a CryptoNote address.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThresholdCryptoNote {
+    pub view: ThresholdShare,
+    pub spend: ThresholdShare,
+    pub public: CryptoNotePublic
+
+} impl ThresholdCryptoNote {
+    ///Split CryptoNote private keys into `participants` shares, any `threshold` of which can receive and spend together.
+    ///
+    ///Returns `SignatureError::Malformed` if `threshold` is 0 or larger than `participants`.
+    pub fn deal(keys: &CryptoNotePrivate, threshold: usize, participants: usize) -> Result<Vec<Self>, SignatureError> {
+        let view = ThresholdShare::deal(&keys.view, threshold, participants)?;
+        let spend = ThresholdShare::deal(&keys.spend, threshold, participants)?;
+        let public = keys.to_public();
+        return Ok(view.into_iter().zip(spend)
+            .map(|(view, spend)| Self{view, spend, public}).collect())
+    }
+
+    ///Calculate this participant's part of the shared secret with a transaction key,
+    ///for the participants in `signers`, which must include this participant.
+    pub fn partial_shared_secret(&self, signers: &[u64], transaction_key: &RistrettoPoint) -> Result<RistrettoPoint, SignatureError> {
+        return self.view.partial_shared_secret(signers, transaction_key)
+    }
+
+    ///"Receive" a payment, decrypting its content, given the pedersen commitment
+    ///and the shared secret combined from the partial shared secrets of all signers.
+    ///
+    ///Returns `Some(ThresholdEnote)` if the enote belongs to these keys, or `None` if not.
+    pub fn receive(
+        &self, recipient: &Recipient, commitment: &Commitment, shared_secret: SharedSecret
+    ) -> Option<ThresholdEnote> {
+        //check view tag
+        if shared_secret.get_view_tag() != recipient.view_tag {
+            return None
+        }
+
+        //check public key and commitment
+        let (offset, _, value, blinding) = shared_secret.open_enote(recipient, commitment, |offset|
+            (self.public.spend + (offset * G) == recipient.public_key).then_some(*offset))?;
+
+        let payment_id = recipient.encrypted_payment_id
+            .map(|encrypted| shared_secret.decrypt_payment_id(encrypted));
+
+        return Some(ThresholdEnote{
+            owner: self.spend.add_offset(&offset),
+            public_key: recipient.public_key,
+            value,
+            blinding,
+            payment_id
+        })
+    }
+
+} impl ToBytes<'_> for ThresholdCryptoNote {}
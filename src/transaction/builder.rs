@@ -0,0 +1,149 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::internal_common::*;
+use crate::signature::{
+    CLSAGSignature,
+    balanced_blindings
+};
+use crate::rangeproof::BulletPlusRangeProof;
+use crate::address::{
+    Address,
+    send_multiple,
+    outgoing::OutgoingViewKey
+};
+use super::*;
+
+
+///Builds a fully signed `Transaction` from owned enotes, rings, and destination addresses.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionBuilder {
+    inputs: Vec<(Ring, EnoteKeys)>,
+    outputs: Vec<(Address, u64)>,
+    fee: u64,
+    share_transaction_key: bool,
+    outgoing: Option<OutgoingViewKey>
+
+} impl TransactionBuilder {
+    ///Create a new, empty transaction builder.
+    pub fn new() -> Self {
+        return Self::default()
+    }
+
+    ///Spend an enote, given its private keys and a ring containing it (along with decoys).
+    ///
+    ///The ring does not need to be sorted, it will be sorted when the transaction is built.
+    pub fn add_input(&mut self, ring: Ring, enote_keys: EnoteKeys) -> &mut Self {
+        self.inputs.push((ring, enote_keys));
+        return self
+    }
+
+    ///Send `amount` to an address.
+    pub fn add_output(&mut self, address: Address, amount: u64) -> &mut Self {
+        self.outputs.push((address, amount));
+        return self
+    }
+
+    ///Use one transaction key for all outputs, instead of one per output, to reduce the transaction's size.
+    ///
+    ///Outputs to subaddresses still need their own transaction key.
+    ///The recipients of the other outputs must receive them with `receive_with_index`
+    ///(or `Scanner::scan_cryptonote_with_transaction_key`),
+    ///using `Transaction::transaction_key` and the index of the output.
+    pub fn share_transaction_key(&mut self) -> &mut Self {
+        self.share_transaction_key = true;
+        return self
+    }
+
+    ///Send every output with an outgoing view key, so the outputs can be recovered later with `OutgoingViewKey::recover`.
+    ///
+    ///Each output then has its own transaction key (derived from the outgoing view key),
+    ///so this takes precedence over `share_transaction_key`.
+    pub fn set_outgoing_view_key(&mut self, outgoing: &OutgoingViewKey) -> &mut Self {
+        self.outgoing = Some(outgoing.to_owned());
+        return self
+    }
+
+    ///Set the transaction fee.
+    pub fn set_fee(&mut self, fee: u64) -> &mut Self {
+        self.fee = fee;
+        return self
+    }
+
+    ///Create the outputs and rangeproof, then sign every input.
+    ///
+    ///The sum of the input values must equal the sum of the output amounts plus the fee.
+    ///
+    ///Returns the signed `Transaction` if successful,
+    ///or `TransactionError` if an error occurred.
+    pub fn build(&self) -> Result<Transaction, TransactionError> {
+        if self.inputs.is_empty() || self.outputs.is_empty() {
+            return Err(TransactionError::Empty)
+        }
+
+        //check balance before doing anything expensive
+        let in_value: u128 = self.inputs.iter()
+            .map(|(_, keys)| keys.value as u128).sum();
+        let out_value: u128 = self.outputs.iter()
+            .map(|(_, amount)| *amount as u128).sum();
+        if in_value != out_value + self.fee as u128 {
+            return Err(TransactionError::Unbalanced)
+        }
+
+        //create outputs
+        let (transaction_key, sent) = match (&self.outgoing, self.share_transaction_key) {
+            (Some(outgoing), _) => (None, self.outputs.iter()
+                .map(|(address, amount)| address.send_with_outgoing(*amount, outgoing)).collect()),
+            (None, true) => {
+                let (_, transaction_key, sent) = send_multiple(&self.outputs);
+                //only include the transaction key if it's used
+                let used = sent.iter().any(|(_, recipient)| recipient.transaction_key.is_none());
+                (Some(transaction_key).filter(|_| used), sent)
+            },
+            (None, false) => (None, self.outputs.iter().map(|(address, amount)| address.send(*amount)).collect())
+        };
+        let amounts: Vec<u64> = self.outputs.iter().map(|(_, amount)| *amount).collect();
+        let (out_blindings, recipients): (Vec<Scalar>, Vec<Recipient>) = sent.into_iter().unzip();
+        let (commitments, rangeproof) = BulletPlusRangeProof::prove(amounts, out_blindings.clone())
+            .map_err(TransactionError::RangeProof)?;
+        let outputs: Vec<TransactionOutput> = recipients.into_iter().zip(commitments)
+            .map(|(recipient, commitment)| TransactionOutput{recipient, commitment}).collect();
+
+        //choose pseudo-out blindings such that the inputs and outputs are balanced
+        let in_blindings = balanced_blindings(self.inputs.len(), &out_blindings);
+
+        //the pseudo-outs are needed for the prefix hash, so calculate them before signing
+        let mut rings: Vec<Ring> = Vec::new();
+        let mut pseudo_outs: Vec<Commitment> = Vec::new();
+        for ((ring, keys), blinding) in self.inputs.iter().zip(&in_blindings) {
+            let mut ring = ring.to_owned();
+            ring.sort();
+            rings.push(ring);
+            pseudo_outs.push(Commitment::commit(keys.value, *blinding));
+        }
+        let prefix_inputs: Vec<(&Ring, Commitment)> = rings.iter().zip(&pseudo_outs)
+            .map(|(ring, pseudo_out)| (ring, *pseudo_out)).collect();
+        let prefix_hash = get_prefix_hash(&prefix_inputs, &outputs, transaction_key, &rangeproof, self.fee);
+
+        //sign inputs
+        let mut inputs: Vec<TransactionInput> = Vec::new();
+        for (i, ((ring, (_, keys)), blinding)) in rings.into_iter()
+            .zip(&self.inputs).zip(in_blindings).enumerate() {
+            let (pseudo_out, signature) = CLSAGSignature::sign(
+                &ring, keys.to_owned(), blinding, &prefix_hash
+            ).map_err(|e| TransactionError::Input(i, e))?;
+            inputs.push(TransactionInput{ring, signature, pseudo_out});
+        }
+
+        return Ok(Transaction{
+            inputs,
+            outputs,
+            transaction_key,
+            rangeproof,
+            fee: self.fee
+        })
+    }
+}
@@ -0,0 +1,288 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{
+    collections::HashSet,
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf}
+};
+
+use crate::internal_common::*;
+
+///Record type for a batch of inserted key images in a `FileKeyImageStore`.
+const RECORD_INSERT: u8 = 1;
+///Record type for a batch of removed key images in a `FileKeyImageStore`.
+const RECORD_REMOVE: u8 = 0;
+///Size of each batch header in a `FileKeyImageStore`: 1 byte record type, and the 4 byte number of key images.
+const HEADER_SIZE: usize = 5;
+///Size of the checksum after each batch in a `FileKeyImageStore`.
+const CHECKSUM_SIZE: usize = 32;
+
+
+///A set of spent key images, used to detect double spends.
+///
+///Batches are atomic: if any key image in a batch is rejected, the store is left unmodified.
+pub trait KeyImageStore {
+    ///Check if a key image is in the store (ie, it has been spent).
+    fn contains(&self, key_image: &RistrettoPoint) -> bool;
+
+    ///Insert a batch of key images, for example all key images of a transaction.
+    ///
+    ///Returns `Err(KeyImageStoreError)` if any key image is already in the store,
+    ///or is duplicated within the batch.
+    fn insert_batch(&mut self, key_images: &[RistrettoPoint]) -> Result<(), KeyImageStoreError>;
+
+    ///Remove a batch of key images, for example when rolling back a transaction.
+    ///
+    ///Returns `Err(KeyImageStoreError)` if any key image is not in the store.
+    fn remove(&mut self, key_images: &[RistrettoPoint]) -> Result<(), KeyImageStoreError>;
+}
+
+///Encode a batch of key images, and check that they can be inserted into `spent`.
+fn check_insert(spent: &HashSet<[u8; 32]>, key_images: &[RistrettoPoint]) -> Result<Vec<[u8; 32]>, KeyImageStoreError> {
+    let mut batch: HashSet<[u8; 32]> = HashSet::new();
+    let mut encoded: Vec<[u8; 32]> = Vec::new();
+    for (i, key_image) in key_images.iter().enumerate() {
+        let key_image = encode_point(key_image);
+        if spent.contains(&key_image) || !batch.insert(key_image) {
+            return Err(KeyImageStoreError::AlreadySpent(i))
+        }
+        encoded.push(key_image);
+    }
+    return Ok(encoded)
+}
+
+///Encode a batch of key images, and check that they can be removed from `spent`.
+fn check_remove(spent: &HashSet<[u8; 32]>, key_images: &[RistrettoPoint]) -> Result<Vec<[u8; 32]>, KeyImageStoreError> {
+    let mut batch: HashSet<[u8; 32]> = HashSet::new();
+    let mut encoded: Vec<[u8; 32]> = Vec::new();
+    for (i, key_image) in key_images.iter().enumerate() {
+        let key_image = encode_point(key_image);
+        //a key image removed twice in the same batch would not be found the second time
+        if !spent.contains(&key_image) || !batch.insert(key_image) {
+            return Err(KeyImageStoreError::NotFound(i))
+        }
+        encoded.push(key_image);
+    }
+    return Ok(encoded)
+}
+
+///Convert an IO error into a `KeyImageStoreError`.
+fn io_error(e: std::io::Error) -> KeyImageStoreError {
+    return KeyImageStoreError::StorageError(e.to_string())
+}
+
+///Calculate the checksum of a batch, given its header and key images.
+fn batch_checksum(batch: &[u8]) -> [u8; 32] {
+    return domain_h_bytes(batch, domains::KEY_IMAGE_STORE_CHECKSUM)
+}
+
+///A batch read from a `FileKeyImageStore`: the record type, the key images, and the size of the batch in the file.
+type Batch = (u8, Vec<[u8; 32]>, usize);
+
+///Read the next batch from the bytes of a `FileKeyImageStore`.
+///
+///Returns the batch,
+///`None` if the batch is cut off by the end of the file (because it was interrupted while being written),
+///or `Err(KeyImageStoreError)` if the checksum of a batch before the last one is invalid.
+fn read_batch(bytes: &[u8]) -> Result<Option<Batch>, KeyImageStoreError> {
+    if bytes.len() < HEADER_SIZE {
+        return Ok(None)
+    }
+    let count = u32::from_le_bytes(bytes[1..HEADER_SIZE].try_into().expect("Wrong header length"));
+    let length = match (count as usize).checked_mul(32).and_then(|size| size.checked_add(HEADER_SIZE)) {
+        Some(length) => length,
+        None => return Ok(None)
+    };
+    if bytes.len() < length + CHECKSUM_SIZE {
+        return Ok(None)
+    }
+
+    if batch_checksum(&bytes[0..length]) != bytes[length..length + CHECKSUM_SIZE] {
+        //only the last batch can be incomplete
+        return match bytes.len() == length + CHECKSUM_SIZE {
+            true => Ok(None),
+            false => Err(KeyImageStoreError::Corrupted)
+        }
+    }
+
+    let key_images: Vec<[u8; 32]> = bytes[HEADER_SIZE..length].chunks(32)
+        .map(|key_image| key_image.try_into().expect("Wrong key image length"))
+        .collect();
+    return Ok(Some((bytes[0], key_images, length + CHECKSUM_SIZE)))
+}
+
+///An in-memory `KeyImageStore`.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryKeyImageStore {
+    spent: HashSet<[u8; 32]>
+
+} impl MemoryKeyImageStore {
+    ///Create a new, empty store.
+    pub fn new() -> Self {
+        return Self::default()
+    }
+
+    ///Return the number of key images in the store.
+    pub fn len(&self) -> usize {
+        return self.spent.len()
+    }
+
+    ///Check if the store is empty.
+    pub fn is_empty(&self) -> bool {
+        return self.spent.is_empty()
+    }
+
+} impl KeyImageStore for MemoryKeyImageStore {
+    fn contains(&self, key_image: &RistrettoPoint) -> bool {
+        return self.spent.contains(&encode_point(key_image))
+    }
+
+    fn insert_batch(&mut self, key_images: &[RistrettoPoint]) -> Result<(), KeyImageStoreError> {
+        for key_image in check_insert(&self.spent, key_images)? {
+            self.spent.insert(key_image);
+        }
+        return Ok(())
+    }
+
+    fn remove(&mut self, key_images: &[RistrettoPoint]) -> Result<(), KeyImageStoreError> {
+        for key_image in check_remove(&self.spent, key_images)? {
+            self.spent.remove(&key_image);
+        }
+        return Ok(())
+    }
+}
+
+
+///A simple file-backed `KeyImageStore`.
+///
+///The file is append-only: each batch of insertions or removals is appended as one record, followed by a checksum,
+///and the batches are replayed into memory when the store is opened.
+///Lookups are done in memory.
+///
+///If a write fails, the file is truncated back to its previous length.
+///If that also fails, the store can't be modified until it is reopened.
+///If the process is interrupted while writing a batch (for example by a crash or power loss),
+///the incomplete batch is discarded when the store is next opened.
+#[derive(Debug)]
+pub struct FileKeyImageStore {
+    path: PathBuf,
+    file: File,
+    spent: HashSet<[u8; 32]>,
+    ///Set if a failed write could not be rolled back
+    failed: bool
+
+} impl FileKeyImageStore {
+    ///Open the store at `path`, creating the file if it does not exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, KeyImageStoreError> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .read(true).append(true).create(true)
+            .open(&path).map_err(io_error)?;
+
+        let mut bytes: Vec<u8> = Vec::new();
+        file.read_to_end(&mut bytes).map_err(io_error)?;
+
+        let mut spent: HashSet<[u8; 32]> = HashSet::new();
+        let mut position = 0;
+        while position < bytes.len() {
+            let (record_type, key_images, length) = match read_batch(&bytes[position..])? {
+                Some(batch) => batch,
+                None => break
+            };
+            for key_image in key_images {
+                let valid = match record_type {
+                    RECORD_INSERT => spent.insert(key_image),
+                    RECORD_REMOVE => spent.remove(&key_image),
+                    _ => false
+                };
+                if !valid {
+                    return Err(KeyImageStoreError::Corrupted)
+                }
+            }
+            position += length;
+        }
+
+        //discard an incomplete last batch
+        if position < bytes.len() {
+            file.set_len(position as u64).and_then(|_| file.sync_data()).map_err(io_error)?;
+        }
+
+        return Ok(Self{path, file, spent, failed: false})
+    }
+
+    ///Return the path of the underlying file.
+    pub fn path(&self) -> &Path {
+        return &self.path
+    }
+
+    ///Return the number of key images in the store.
+    pub fn len(&self) -> usize {
+        return self.spent.len()
+    }
+
+    ///Check if the store is empty.
+    pub fn is_empty(&self) -> bool {
+        return self.spent.is_empty()
+    }
+
+    ///Append a batch to the file.
+    ///
+    ///If the write fails, the file is truncated to its previous length,
+    ///so that a partially written batch doesn't remain before later batches.
+    fn append(&mut self, record_type: u8, key_images: &[[u8; 32]]) -> Result<(), KeyImageStoreError> {
+        if self.failed {
+            return Err(KeyImageStoreError::Corrupted)
+        }
+        if key_images.is_empty() {
+            return Ok(())
+        }
+
+        let count: u32 = key_images.len().try_into()
+            .map_err(|_| KeyImageStoreError::Unspecified("Too many key images in one batch.".to_string()))?;
+        let mut bytes: Vec<u8> = Vec::with_capacity(HEADER_SIZE + (key_images.len() * 32) + CHECKSUM_SIZE);
+        bytes.push(record_type);
+        bytes.extend(count.to_le_bytes());
+        for key_image in key_images {
+            bytes.extend(key_image);
+        }
+        let checksum = batch_checksum(&bytes);
+        bytes.extend(checksum);
+
+        let length = self.file.metadata().map_err(io_error)?.len();
+        if let Err(e) = self.file.write_all(&bytes).and_then(|_| self.file.sync_data()) {
+            if self.file.set_len(length).and_then(|_| self.file.sync_data()).is_err() {
+                self.failed = true;
+            }
+            return Err(io_error(e))
+        }
+        return Ok(())
+    }
+
+} impl KeyImageStore for FileKeyImageStore {
+    fn contains(&self, key_image: &RistrettoPoint) -> bool {
+        return self.spent.contains(&encode_point(key_image))
+    }
+
+    fn insert_batch(&mut self, key_images: &[RistrettoPoint]) -> Result<(), KeyImageStoreError> {
+        let encoded = check_insert(&self.spent, key_images)?;
+        self.append(RECORD_INSERT, &encoded)?;
+        for key_image in encoded {
+            self.spent.insert(key_image);
+        }
+        return Ok(())
+    }
+
+    fn remove(&mut self, key_images: &[RistrettoPoint]) -> Result<(), KeyImageStoreError> {
+        let encoded = check_remove(&self.spent, key_images)?;
+        self.append(RECORD_REMOVE, &encoded)?;
+        for key_image in encoded {
+            self.spent.remove(&key_image);
+        }
+        return Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! RingCT transactions, built from CLSAG signatures, Bulletproofs+ rangeproofs, and stealth addresses.
+//!
+//! This is a minimal, canonical transaction format.
+//! It is not compatible with Monero's, or any other implementation's, transactions.
+
+mod builder;
+mod verify;
+mod key_image_store;
+
+pub use builder::TransactionBuilder;
+pub use key_image_store::{
+    KeyImageStore,
+    MemoryKeyImageStore,
+    FileKeyImageStore
+};
+
+use crate::internal_common::*;
+use crate::signature::CLSAGSignature;
+use crate::rangeproof::BulletPlusRangeProof;
+use crate::address::Recipient;
+
+
+///Create the transaction prefix hash, which is the message signed by every input.
+///
+///This commits to every ring, pseudo-out, output, the rangeproof, and the fee,
+///so that none of them can be modified without invalidating the signatures.
+fn get_prefix_hash(
+    inputs: &[(&Ring, Commitment)], outputs: &[TransactionOutput], transaction_key: Option<RistrettoPoint>,
+    rangeproof: &BulletPlusRangeProof, fee: u64
+) -> [u8; 32] {
+    //all non-point data, including the length of each section,
+    //so that the layout of the points is unambiguous
+    let mut framing: Vec<u8> = Vec::new();
+    let mut points: Vec<RistrettoPoint> = Vec::new();
+
+    framing.extend(fee.to_le_bytes());
+
+    framing.extend((inputs.len() as u64).to_le_bytes());
+    for (ring, pseudo_out) in inputs {
+        framing.extend((ring.0.len() as u64).to_le_bytes());
+        for enote in &ring.0 {
+            points.push(enote.owner);
+            points.push(enote.commitment.0);
+        }
+        points.push(pseudo_out.0);
+    }
+
+    match transaction_key {
+        Some(key) => {
+            framing.push(1);
+            points.push(key);
+        },
+        None => framing.push(0)
+    }
+
+    framing.extend((outputs.len() as u64).to_le_bytes());
+    for output in outputs {
+        let recipient = &output.recipient;
+        points.push(recipient.public_key);
+        match recipient.transaction_key {
+            Some(key) => {
+                framing.push(1);
+                points.push(key);
+            },
+            None => framing.push(0)
+        }
+        framing.push(recipient.view_tag);
+        framing.extend(recipient.encrypted_amount.to_le_bytes());
+        match recipient.encrypted_payment_id {
+            Some(payment_id) => {
+                framing.push(1);
+                framing.extend(payment_id);
+            },
+            None => framing.push(0)
+        }
+        match recipient.auxiliary_key {
+            Some(key) => {
+                framing.push(1);
+                points.push(key);
+            },
+            None => framing.push(0)
+        }
+        match recipient.encrypted_destination {
+            Some(destination) => {
+                framing.push(1);
+                framing.extend(destination.nonce);
+                framing.extend(destination.encrypted_view);
+            },
+            None => framing.push(0)
+        }
+        points.push(output.commitment.0);
+    }
+
+    let rangeproof = rangeproof.to_bytes().expect("Failed to encode rangeproof");
+    framing.extend((rangeproof.len() as u64).to_le_bytes());
+    framing.extend(rangeproof);
+
+    return domain_h_bytes(
+        &[framing, batch_encode_points(&points).concat()].concat(),
+        domains::TRANSACTION_PREFIX
+    )
+}
+
+
+///A transaction input: a ring of enotes, a signature proving ownership of one of them,
+///and the input commitment (aka "pseudo-out").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionInput {
+    pub ring: Ring,
+    pub signature: CLSAGSignature,
+    pub pseudo_out: Commitment
+
+} impl ToBytes<'_> for TransactionInput {}
+
+///A transaction output: the data needed for the recipient to detect the payment,
+///and the commitment to its value.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransactionOutput {
+    pub recipient: Recipient,
+    pub commitment: Commitment
+
+} impl TransactionOutput {
+    ///Return the enote created by this output.
+    pub fn to_enote(&self) -> Enote {
+        return self.recipient.to_enote(&self.commitment)
+    }
+
+} impl ToBytes<'_> for TransactionOutput {}
+
+///A RingCT transaction.
+///
+///To create a transaction, use `TransactionBuilder`.
+///To verify a transaction, use `verify`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    pub inputs: Vec<TransactionInput>,
+    pub outputs: Vec<TransactionOutput>,
+    ///Transaction key shared between all outputs without their own transaction key,
+    ///see `address::send_multiple`
+    pub transaction_key: Option<RistrettoPoint>,
+    ///Aggregated rangeproof for all output commitments
+    pub rangeproof: BulletPlusRangeProof,
+    pub fee: u64
+
+} impl Transaction {
+    ///Return the transaction prefix hash.
+    ///This is the message which is signed by every input.
+    pub fn prefix_hash(&self) -> [u8; 32] {
+        let inputs: Vec<(&Ring, Commitment)> = self.inputs.iter()
+            .map(|input| (&input.ring, input.pseudo_out)).collect();
+        return get_prefix_hash(&inputs, &self.outputs, self.transaction_key, &self.rangeproof, self.fee)
+    }
+
+    ///Return the key images of all inputs, in order.
+    pub fn key_images(&self) -> Vec<RistrettoPoint> {
+        return self.inputs.iter()
+            .map(|input| input.signature.key_image).collect()
+    }
+
+} impl ToBytes<'_> for Transaction {}
@@ -0,0 +1,100 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashSet;
+
+use crate::internal_common::*;
+use crate::signature::CLSAGSignature;
+use crate::rangeproof::BulletPlusRangeProof;
+use super::*;
+
+
+impl Transaction {
+    ///Fully verify this transaction.
+    ///
+    ///The checks are done in order of cost:
+    /// 1. the transaction has at least 1 input and 1 output
+    /// 2. no key image is used by more than 1 input (or already spent, when using `verify_with_store`)
+    /// 3. no public key is used by more than 1 output
+    /// 4. the inputs are balanced with the outputs and fee
+    /// 5. the rangeproof is valid
+    /// 6. every input's signature is valid, and signs the prefix hash
+    ///
+    ///Returns `Ok()` if the transaction is valid,
+    ///or `Err(TransactionError)` naming the first component which failed.
+    ///
+    ///Note that this does not check whether the key images have already been spent in other transactions,
+    ///use `verify_with_store` for that.
+    pub fn verify(&self) -> Result<(), TransactionError> {
+        return self.verify_internal(None)
+    }
+
+    ///Same as `verify`, except it also rejects the transaction if any key image is already in `store`.
+    ///
+    ///This does not insert the key images into `store`.
+    ///Once the transaction is accepted, use `store.insert_batch(&transaction.key_images())`.
+    pub fn verify_with_store(&self, store: &dyn KeyImageStore) -> Result<(), TransactionError> {
+        return self.verify_internal(Some(store))
+    }
+
+    ///Internal verification function.
+    fn verify_internal(&self, store: Option<&dyn KeyImageStore>) -> Result<(), TransactionError> {
+        if self.inputs.is_empty() || self.outputs.is_empty() {
+            return Err(TransactionError::Empty)
+        }
+
+        //key images must be unique
+        let encoded_key_images = batch_encode_points(&self.key_images());
+        let mut seen: HashSet<[u8; 32]> = HashSet::new();
+        for (i, key_image) in encoded_key_images.into_iter().enumerate() {
+            if !seen.insert(key_image) {
+                return Err(TransactionError::DuplicateKeyImage(i))
+            }
+        }
+
+        //key images must be unspent
+        if let Some(store) = store {
+            for (i, input) in self.inputs.iter().enumerate() {
+                if store.contains(&input.signature.key_image) {
+                    return Err(TransactionError::KeyImageSpent(i))
+                }
+            }
+        }
+
+        //output keys must be unique
+        let output_keys: Vec<RistrettoPoint> = self.outputs.iter()
+            .map(|output| output.recipient.public_key).collect();
+        let mut seen: HashSet<[u8; 32]> = HashSet::new();
+        for (i, output_key) in batch_encode_points(&output_keys).into_iter().enumerate() {
+            if !seen.insert(output_key) {
+                return Err(TransactionError::DuplicateOutputKey(i))
+            }
+        }
+
+        //balance
+        let in_commitments: Vec<Commitment> = self.inputs.iter()
+            .map(|input| input.pseudo_out).collect();
+        let out_commitments: Vec<Commitment> = self.outputs.iter()
+            .map(|output| output.commitment).collect();
+        if !Commitment::is_balanced(in_commitments, out_commitments.clone(), self.fee) {
+            return Err(TransactionError::Unbalanced)
+        }
+
+        //rangeproof
+        BulletPlusRangeProof::verify(out_commitments, self.rangeproof.to_owned())
+            .map_err(TransactionError::RangeProof)?;
+
+        //signatures
+        let prefix_hash = self.prefix_hash();
+        for (i, input) in self.inputs.iter().enumerate() {
+            CLSAGSignature::verify(
+                input.signature.to_owned(), &input.ring, input.pseudo_out, &prefix_hash
+            ).map_err(|e| TransactionError::Input(i, e))?;
+        }
+
+        return Ok(())
+    }
+}
@@ -9,6 +9,7 @@ use std::iter::Sum;
 use crate::tobytes::*;
 use crate::curve::*;
 use crate::pedersen::*;
+use crate::asset::{AssetId, AssetTag};
 use crate::signature::{
     encode_rings,
     separate_ring,
@@ -48,6 +49,21 @@ pub struct Commitment(
         return Commitment::sum(in_commitments.into_iter()) == Commitment::sum(out.into_iter())
     }
 
+    ///Given input asset commitments, output asset commitments, and "extra" output of each asset (ie fees),
+    ///check if the equation is balanced.
+    ///
+    ///`in == (out + extra)`, where the extra amounts are committed to using their unblinded asset generators.
+    ///
+    ///See the `asset` module for more information.
+    pub fn is_balanced_with_assets(
+        in_commitments: Vec<Commitment>, out_commitments: Vec<Commitment>, extra: Vec<(AssetId, u64)>
+    ) -> bool {
+        let extra: Vec<Commitment> = extra.iter()
+            .map(|(asset, value)| AssetTag::unblinded(asset).commit(*value, Scalar::zero())).collect();
+        let out = [ out_commitments, extra ].concat();
+        return Commitment::sum(in_commitments.into_iter()) == Commitment::sum(out.into_iter())
+    }
+
     ///given a `Vec` of commitments, convert them into `RistrettoPoint`'s
     pub(crate) fn to_ristretto(commitments: Vec<Commitment>) -> Vec<RistrettoPoint> {
         return commitments.into_iter().map(|com| com.0).collect();
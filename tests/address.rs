@@ -2,12 +2,25 @@
 
 use ringct::{
     ToBytes,
+    errors::{
+        AddressError,
+        MnemonicError
+    },
     address::{
+        Address,
+        Recipient,
+        send_multiple,
+        encoding::Network,
+        mnemonic::Mnemonic,
+        scanner::Scanner,
+        outgoing::OutgoingViewKey,
         ECDHPublicKey,
         SharedSecret,
+        DerivationVersion,
         cryptonote::{
             CryptoNotePublic,
-            CryptoNotePrivateView
+            CryptoNotePrivateView,
+            IntegratedAddress
         },
         subaddress::{
             SubaddressPublic,
@@ -172,4 +185,377 @@ fn subaddress_test() {
     let (blinding, recipient) = pk1.send(100);
     let commitment = Commitment::commit(100, blinding);
     view_only.receive(&recipient, &commitment).unwrap();
-}
\ No newline at end of file
+}
+
+#[test]
+fn address_encoding_test() {
+    let cryptonote = Address::from(CryptoNotePrivate::generate().to_public());
+    let mut master_keys = MasterPrivateKeys::generate();
+    master_keys.init_coordinates((0, 1));
+    let subaddress = Address::from(master_keys.get_subaddress((0, 1)).unwrap());
+
+    for address in [cryptonote, subaddress] {
+        let encoded = address.encode(Network::Mainnet);
+        assert!(Address::decode(&encoded, Network::Mainnet).unwrap() == address);
+        assert!(matches!(Address::decode(&encoded, Network::Testnet), Err(AddressError::WrongNetwork)));
+        let testnet = address.encode(Network::Testnet);
+        assert!(testnet != encoded);
+        assert!(Address::decode(&testnet, Network::Testnet).unwrap() == address);
+
+        //a typo
+        let mut typo = encoded.clone().into_bytes();
+        typo[20] = if typo[20] == b'a' { b'b' } else { b'a' };
+        let typo = String::from_utf8(typo).unwrap();
+        assert!(matches!(Address::decode(&typo, Network::Mainnet), Err(AddressError::InvalidChecksum)));
+
+        //invalid characters and lengths
+        assert!(matches!(Address::decode(&encoded.replace(&encoded[5..6], "0"), Network::Mainnet),
+            Err(AddressError::InvalidCharacter)));
+        assert!(matches!(Address::decode(&encoded[5..], Network::Mainnet), Err(AddressError::InvalidLength)));
+    }
+
+    //the same keys, with a different address type
+    let keys = CryptoNotePrivate::generate().to_public();
+    let subaddress = Address::from(SubaddressPublic{view: keys.view, spend: keys.spend});
+    assert!(Address::from(keys).encode(Network::Mainnet) != subaddress.encode(Network::Mainnet));
+}
+
+#[test]
+fn integrated_address_test() {
+    let keys = CryptoNotePrivate::generate();
+    let address = IntegratedAddress::new(keys.to_public(), *b"invoice1");
+
+    //payment ID is encrypted
+    let (blinding, recipient) = address.send(100);
+    let commitment = Commitment::commit(100, blinding);
+    assert!(recipient.encrypted_payment_id.unwrap() != *b"invoice1");
+
+    let (enote_keys, payment_id) = keys.receive(&recipient, &commitment).unwrap();
+    assert!(enote_keys.value == 100);
+    assert!(payment_id == Some(*b"invoice1"));
+    let (_, _, payment_id) = keys.to_view_only().receive(&recipient, &commitment).unwrap();
+    assert!(payment_id == Some(*b"invoice1"));
+
+    //with a transaction key
+    let transaction_key = Scalar::generate();
+    let (blinding, recipient) = address.send_with_key(100, transaction_key);
+    let commitment = Commitment::commit(100, blinding);
+    let (_, payment_id) = keys.receive_with_key(&recipient, &commitment, transaction_key.to_public()).unwrap();
+    assert!(payment_id == Some(*b"invoice1"));
+
+    //normal addresses don't have a payment ID
+    let (blinding, recipient) = keys.to_public().send(100);
+    let (_, payment_id) = keys.receive(&recipient, &Commitment::commit(100, blinding)).unwrap();
+    assert!(payment_id.is_none());
+
+    //with a shared transaction key
+    let (transaction_sk, transaction_key, sent) = send_multiple(
+        &[(Address::from(keys.to_public()), 50), (Address::from(address), 100)]
+    );
+    assert!(transaction_key == transaction_sk.to_public());
+    let (blinding, recipient) = &sent[1];
+    let commitment = Commitment::commit(100, *blinding);
+    assert!(recipient.transaction_key.is_none());
+    let (_, _, payment_id) = keys.to_view_only().receive_with_index(recipient, &commitment, transaction_key, 1).unwrap();
+    assert!(payment_id == Some(*b"invoice1"));
+
+    //encoding
+    let address = Address::from(IntegratedAddress::generate(keys.to_public()));
+    let encoded = address.encode(Network::Mainnet);
+    assert!(Address::decode(&encoded, Network::Mainnet).unwrap() == address);
+    assert!(encoded != Address::from(keys.to_public()).encode(Network::Mainnet));
+}
+
+#[test]
+fn mnemonic_test() {
+    let mnemonic = Mnemonic::generate();
+    let phrase = mnemonic.to_phrase();
+    assert!(phrase.split(' ').count() == 24);
+    //the seed uses all 256 bits, unlike a reduced scalar
+    assert!((0..64).any(|_| Mnemonic::generate().to_seed()[31] >= 0x20));
+
+    //round trip, ignoring case and extra whitespace
+    let decoded = Mnemonic::from_phrase(&phrase).unwrap();
+    assert!(decoded == mnemonic);
+    let decoded = Mnemonic::from_phrase(&format!("  {}\n", phrase.to_uppercase().replace(' ', "\t "))).unwrap();
+    assert!(decoded == mnemonic);
+    assert!(Mnemonic::from_seed([0; 32]).to_phrase().starts_with("abandon abandon"));
+
+    //the same keys
+    assert!(CryptoNotePrivate::from_seed(decoded.to_seed()) == CryptoNotePrivate::from_seed(mnemonic.to_seed()));
+
+    //passphrases
+    assert!(mnemonic.to_seed_with_passphrase("abc") == decoded.to_seed_with_passphrase("abc"));
+    assert!(mnemonic.to_seed_with_passphrase("abc") != mnemonic.to_seed_with_passphrase("abd"));
+    assert!(mnemonic.to_seed_with_passphrase("") != mnemonic.to_seed());
+
+    //invalid phrases
+    let mut words = mnemonic.to_words();
+    assert!(matches!(Mnemonic::from_phrase(&words[1..].join(" ")), Err(MnemonicError::InvalidWordCount)));
+    words[3] = "notaword";
+    assert!(matches!(Mnemonic::from_phrase(&words.join(" ")), Err(MnemonicError::UnknownWord(3))));
+    //a fixed seed, so that the checksum is guaranteed to catch the typo
+    let mut words = Mnemonic::from_seed([7; 32]).to_words();
+    words[0] = if words[0] == "zoo" { "abandon" } else { "zoo" };
+    assert!(matches!(Mnemonic::from_phrase(&words.join(" ")), Err(MnemonicError::InvalidChecksum)));
+}
+
+#[test]
+fn janus_test() {
+    let mut master_keys = MasterPrivateKeys::generate();
+    master_keys.init(2, 1);
+    let subaddress_1 = master_keys.get_subaddress((0, 0)).unwrap();
+    let subaddress_2 = master_keys.get_subaddress((1, 0)).unwrap();
+
+    //hardened payments are received normally
+    let (blinding, recipient) = subaddress_1.send_hardened(100);
+    let commitment = Commitment::commit(100, blinding);
+    assert!(recipient.auxiliary_key.is_some());
+    assert!(master_keys.receive_hardened(&recipient, &commitment).unwrap().value == 100);
+    assert!(master_keys.receive(&recipient, &commitment).is_some());
+
+    //unhardened payments are rejected by the hardened check
+    let (blinding, recipient) = subaddress_1.send(100);
+    let commitment = Commitment::commit(100, blinding);
+    assert!(master_keys.receive_hardened(&recipient, &commitment).is_none());
+
+    //Janus attack: the transaction key uses subaddress 1, but the public key uses subaddress 2
+    let transaction_sk = Scalar::generate();
+    let shared_secret = subaddress_1.view.shared_secret(transaction_sk);
+    let recipient = Recipient {
+        public_key: subaddress_2.derive_key_with_version(shared_secret.clone(), DerivationVersion::CURRENT),
+        transaction_key: Some(transaction_sk * subaddress_1.spend),
+        view_tag: shared_secret.get_view_tag(),
+        encrypted_amount: shared_secret.encrypt_amount_with_version(100, DerivationVersion::CURRENT),
+        encrypted_payment_id: None,
+        auxiliary_key: Some(transaction_sk.to_public()),
+        encrypted_destination: None
+    };
+    let commitment = Commitment::commit(100, shared_secret.blinding(DerivationVersion::CURRENT));
+    //the unhardened receiver would confirm that both subaddresses belong to the same wallet
+    assert!(master_keys.receive(&recipient, &commitment).is_some());
+    assert!(master_keys.receive_hardened(&recipient, &commitment).is_none());
+}
+
+#[test]
+fn lookahead_test() {
+    let mut sender_view = MasterPrivateKeys::from_seed([1; 32]);
+    sender_view.init(8, 1);
+    assert!(sender_view.get_subaddress((0, 0)).unwrap() != sender_view.get_subaddress((1, 0)).unwrap());
+
+    let mut master_keys = MasterPrivateKeys::from_seed([1; 32]);
+    master_keys.init_lookahead(2, 5);
+    let mut view_only = master_keys.to_view_only();
+    view_only.init_lookahead(2, 5);
+
+    //receiving without extending doesn't move the window
+    let (blinding, recipient) = sender_view.get_subaddress((1, 0)).unwrap().send(100);
+    assert!(master_keys.receive(&recipient, &Commitment::commit(100, blinding)).is_some());
+    let (blinding, recipient) = sender_view.get_subaddress((2, 0)).unwrap().send(100);
+    assert!(master_keys.receive(&recipient, &Commitment::commit(100, blinding)).is_none());
+
+    //each payment is within the window moved by the previous payment
+    for coordinates in [(1, 0), (2, 0), (3, 0), (4, 0)] {
+        let (blinding, recipient) = sender_view.get_subaddress(coordinates).unwrap().send(100);
+        let commitment = Commitment::commit(100, blinding);
+        assert!(master_keys.receive_and_extend(&recipient, &commitment).is_some());
+        assert!(view_only.receive_and_extend(&recipient, &commitment).is_some());
+    }
+
+    //too far past the window
+    let (blinding, recipient) = sender_view.get_subaddress((7, 0)).unwrap().send(100);
+    let commitment = Commitment::commit(100, blinding);
+    assert!(master_keys.receive(&recipient, &commitment).is_none());
+    assert!(view_only.receive(&recipient, &commitment).is_none());
+}
+
+#[test]
+fn scanner_test() {
+    let cryptonote_keys = CryptoNotePrivate::generate();
+    let mut master_keys = MasterPrivateKeys::generate();
+    master_keys.init(3, 1);
+    let other = CryptoNotePrivate::generate().to_public();
+
+    //mostly unrelated outputs, including ones for subaddresses past the lookahead window
+    let mut outputs: Vec<(Recipient, Commitment)> = Vec::new();
+    let mut send = |address: Address, amount: u64| {
+        let (blinding, recipient) = address.send(amount);
+        outputs.push((recipient, Commitment::commit(amount, blinding)));
+    };
+    for i in 0..600u64 {
+        match i {
+            5 | 377 => send(Address::from(cryptonote_keys.to_public()), i),
+            123 => send(Address::from(IntegratedAddress::new(cryptonote_keys.to_public(), *b"invoice1")), i),
+            50 => send(Address::from(master_keys.get_subaddress((2, 0)).unwrap()), i),
+            400 => send(Address::from(master_keys.get_subaddress((1, 0)).unwrap()), i),
+            _ => send(Address::from(other), i)
+        }
+    }
+    //an invalid commitment
+    outputs[377].1 = Commitment::commit(1, Scalar::generate());
+
+    //CryptoNote
+    let view_only = cryptonote_keys.to_view_only();
+    for threads in [1, 3, 8] {
+        let scanned = Scanner::with_threads(threads).scan_cryptonote(&view_only, &outputs);
+        assert!(scanned.iter().map(|output| output.index).collect::<Vec<_>>() == vec!(5, 123));
+        assert!(scanned[1].value == 123 && scanned[1].payment_id == Some(*b"invoice1"));
+        for output in scanned {
+            let (recipient, commitment) = &outputs[output.index];
+            let (value, blinding, _) = view_only.receive(recipient, commitment).unwrap();
+            assert!(output.value == value && output.blinding == blinding);
+        }
+    }
+
+    //Subaddress, where (2, 0) is only found after the window is moved by (1, 0)
+    let mut view_only = master_keys.to_view_only();
+    view_only.init_lookahead(2, 1);
+    let scanned = Scanner::new().scan_subaddress(&mut view_only, &outputs);
+    assert!(scanned.iter().map(|output| output.index).collect::<Vec<_>>() == vec!(50, 400));
+    assert!(scanned[0].coordinates == Some((2, 0)) && scanned[0].value == 50);
+    assert!(scanned[1].coordinates == Some((1, 0)) && scanned[1].value == 400);
+}
+
+#[test]
+fn scanner_shared_key_test() {
+    let keys = CryptoNotePrivate::generate();
+    let mut master_keys = MasterPrivateKeys::generate();
+    master_keys.init(3, 1);
+    let other = CryptoNotePrivate::generate().to_public();
+
+    //one transaction, where the subaddress output has its own transaction key
+    let (_, transaction_key, sent) = send_multiple(&[
+        (Address::from(other), 10),
+        (Address::from(keys.to_public()), 20),
+        (Address::from(master_keys.get_subaddress((2, 0)).unwrap()), 30),
+        (Address::from(IntegratedAddress::new(keys.to_public(), *b"invoice1")), 40),
+        (Address::from(keys.to_public()), 50)
+    ]);
+    let outputs: Vec<(Recipient, Commitment)> = sent.into_iter().zip([10, 20, 30, 40, 50])
+        .map(|((blinding, recipient), amount)| (recipient, Commitment::commit(amount, blinding)))
+        .collect();
+    assert!(outputs.iter().filter(|(recipient, _)| recipient.transaction_key.is_none()).count() == 4);
+
+    //without the transaction key, the shared key outputs are not found
+    let view_only = keys.to_view_only();
+    assert!(Scanner::new().scan_cryptonote(&view_only, &outputs).is_empty());
+
+    for threads in [1, 2, 8] {
+        let scanned = Scanner::with_threads(threads).scan_cryptonote_with_transaction_key(&view_only, &outputs, transaction_key);
+        assert!(scanned.iter().map(|output| output.index).collect::<Vec<_>>() == vec!(1, 3, 4));
+        assert!(scanned.iter().map(|output| output.value).collect::<Vec<_>>() == vec!(20, 40, 50));
+        assert!(scanned[1].payment_id == Some(*b"invoice1"));
+        for output in scanned {
+            let (recipient, commitment) = &outputs[output.index];
+            let (value, blinding, _) = view_only.receive_with_index(recipient, commitment, transaction_key, output.index as u64).unwrap();
+            assert!(output.value == value && output.blinding == blinding);
+        }
+    }
+
+    //the subaddress output is scanned normally
+    let mut master_view = master_keys.to_view_only();
+    master_view.init(3, 1);
+    let scanned = Scanner::new().scan_subaddress(&mut master_view, &outputs);
+    assert!(scanned.len() == 1 && scanned[0].index == 2 && scanned[0].coordinates == Some((2, 0)));
+}
+
+#[test]
+fn derivation_version_test() {
+    //new enotes use different key offsets and blinding factors
+    let keys = CryptoNotePrivate::generate();
+    let (blinding, recipient) = keys.to_public().send(100);
+    let shared_secret = keys.shared_secret(&recipient.transaction_key.unwrap());
+    assert!(blinding == shared_secret.blinding(DerivationVersion::V1));
+    assert!(blinding != shared_secret.key_offset(DerivationVersion::V1));
+    assert!(recipient.public_key == keys.to_public().spend + (&shared_secret.key_offset(DerivationVersion::V1) * G));
+    assert!(recipient.public_key == keys.to_public().derive_key_with_version(shared_secret.clone(), DerivationVersion::V1));
+    assert!(keys.derive_key_with_version(shared_secret.clone(), DerivationVersion::V1).to_public() == recipient.public_key);
+    //the unversioned methods use the legacy derivation
+    assert!(keys.to_public().derive_key(shared_secret.clone()) == keys.to_public().spend + (&shared_secret.as_scalar() * G));
+    assert!(keys.to_view_only().derive_key(shared_secret.clone()) == keys.to_public().derive_key(shared_secret.clone()));
+
+    //old enotes, where the key offset and blinding factor are both the shared secret
+    let legacy_send = |view: RistrettoPoint, spend: RistrettoPoint, transaction_key: RistrettoPoint, transaction_sk: Scalar| {
+        let shared_secret = CryptoNotePublic{view, spend}.shared_secret(transaction_sk);
+        let recipient = Recipient {
+            public_key: spend.derive_key(shared_secret.clone()),
+            transaction_key: Some(transaction_key),
+            view_tag: shared_secret.get_view_tag(),
+            encrypted_amount: shared_secret.encrypt_amount(100),
+            encrypted_payment_id: None,
+            auxiliary_key: None,
+            encrypted_destination: None
+        };
+        return (recipient, Commitment::commit(100, shared_secret.as_scalar()))
+    };
+
+    //CryptoNote
+    let transaction_sk = Scalar::generate();
+    let public = keys.to_public();
+    let (recipient, commitment) = legacy_send(public.view, public.spend, transaction_sk.to_public(), transaction_sk);
+    let (enote, _) = keys.receive(&recipient, &commitment).unwrap();
+    assert!(enote.value == 100 && enote.blinding == keys.shared_secret(&transaction_sk.to_public()).as_scalar());
+    assert!(&enote.owner * G == recipient.public_key);
+    assert!(keys.to_view_only().receive(&recipient, &commitment).unwrap().0 == 100);
+    let scanned = Scanner::new().scan_cryptonote(&keys.to_view_only(), &[(recipient, commitment)]);
+    assert!(scanned.len() == 1 && scanned[0].value == 100);
+
+    //Subaddress
+    let mut master_keys = MasterPrivateKeys::generate();
+    master_keys.init(3, 1);
+    let subaddress = master_keys.get_subaddress((2, 0)).unwrap();
+    let transaction_sk = Scalar::generate();
+    let (recipient, commitment) = legacy_send(
+        subaddress.view, subaddress.spend, transaction_sk.to_public_with_base(subaddress.spend), transaction_sk
+    );
+    let enote = master_keys.receive(&recipient, &commitment).unwrap();
+    assert!(enote.value == 100 && &enote.owner * G == recipient.public_key);
+    let mut view_only = master_keys.to_view_only();
+    view_only.init(3, 1);
+    assert!(view_only.receive(&recipient, &commitment).unwrap().0 == 100);
+    let scanned = Scanner::new().scan_subaddress(&mut view_only, &[(recipient, commitment)]);
+    assert!(scanned.len() == 1 && scanned[0].coordinates == Some((2, 0)));
+}
+
+#[test]
+fn outgoing_test() {
+    let sender = CryptoNotePrivate::from_seed([3; 32]);
+    let outgoing = sender.outgoing_view_key();
+    assert!(outgoing != CryptoNotePrivate::from_seed([4; 32]).outgoing_view_key());
+    assert!(OutgoingViewKey::from_bytes(&outgoing.to_bytes().unwrap()).unwrap() == outgoing);
+
+    let cryptonote_keys = CryptoNotePrivate::generate();
+    let mut master_keys = MasterPrivateKeys::generate();
+    master_keys.init(1, 4);
+    let destinations = vec!(
+        Address::from(cryptonote_keys.to_public()),
+        Address::from(master_keys.get_subaddress((0, 3)).unwrap()),
+        Address::from(IntegratedAddress::new(cryptonote_keys.to_public(), *b"invoice2"))
+    );
+    let sent: Vec<(Recipient, Commitment)> = destinations.iter().enumerate()
+        .map(|(i, address)| {
+            let (blinding, recipient) = address.send_with_outgoing(100 + i as u64, &outgoing);
+            (recipient, Commitment::commit(100 + i as u64, blinding))
+        })
+        .collect();
+
+    //the recipients receive the payments as usual
+    assert!(cryptonote_keys.receive(&sent[0].0, &sent[0].1).unwrap().0.value == 100);
+    assert!(master_keys.receive(&sent[1].0, &sent[1].1).unwrap().value == 101);
+    assert!(cryptonote_keys.receive(&sent[2].0, &sent[2].1).unwrap().1 == Some(*b"invoice2"));
+
+    //the sender recovers the payments, with keys restored from the seed
+    let restored = CryptoNotePrivate::from_seed([3; 32]).outgoing_view_key();
+    for (i, (recipient, commitment)) in sent.iter().enumerate() {
+        let recovered = restored.recover(recipient, commitment).unwrap();
+        assert!(recovered.destination == destinations[i]);
+        assert!(recovered.value == 100 + i as u64);
+        assert!(Commitment::commit(recovered.value, recovered.blinding) == *commitment);
+    }
+
+    //other keys and other payments are not recovered
+    let other = MasterPrivateKeys::generate().outgoing_view_key();
+    assert!(other.recover(&sent[0].0, &sent[0].1).is_none());
+    let (blinding, recipient) = destinations[0].send(100);
+    assert!(restored.recover(&recipient, &Commitment::commit(100, blinding)).is_none());
+}
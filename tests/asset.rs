@@ -0,0 +1,96 @@
+// SPDX short identifier: Unlicense
+
+use ringct::{
+    common::*,
+    rangeproof::BulletPlusRangeProof,
+    asset::{
+        AssetId,
+        AssetTag,
+        SurjectionProof
+    }
+};
+
+///An asset commitment, and its openings
+struct AssetOpening {
+    asset: AssetId,
+    tag_blinding: Scalar,
+    value: u64,
+    blinding: Scalar
+}
+
+impl AssetOpening {
+    fn tag(&self) -> AssetTag {
+        return AssetTag::new(&self.asset, self.tag_blinding)
+    }
+
+    fn commitment(&self) -> Commitment {
+        return self.tag().commit(self.value, self.blinding)
+    }
+
+    ///the total blinding factor of the commitment, relative to `H_a`
+    fn total_blinding(&self) -> Scalar {
+        return (Scalar::from(self.value) * self.tag_blinding) + self.blinding
+    }
+}
+
+#[test]
+fn asset_test() {
+    let asset_a = AssetId::from_name(b"asset a");
+    let asset_b = AssetId::from_name(b"asset b");
+    assert!(asset_a != asset_b);
+    assert!(asset_a.generator() != asset_b.generator());
+
+    let inputs = vec!(
+        AssetOpening{asset: asset_a, tag_blinding: Scalar::generate(), value: 100, blinding: Scalar::generate()},
+        AssetOpening{asset: asset_b, tag_blinding: Scalar::generate(), value: 50, blinding: Scalar::generate()}
+    );
+    let mut outputs = vec!(
+        AssetOpening{asset: asset_a, tag_blinding: Scalar::generate(), value: 70, blinding: Scalar::generate()},
+        AssetOpening{asset: asset_b, tag_blinding: Scalar::generate(), value: 50, blinding: Scalar::generate()},
+        AssetOpening{asset: asset_a, tag_blinding: Scalar::generate(), value: 25, blinding: Scalar::zero()}
+    );
+    //choose the last blinding factor such that the inputs and outputs are balanced
+    let in_blinding: Scalar = inputs.iter().map(|input| input.total_blinding()).sum();
+    let out_blinding: Scalar = outputs.iter().map(|output| output.total_blinding()).sum();
+    outputs[2].blinding = in_blinding - out_blinding;
+
+    let input_tags: Vec<AssetTag> = inputs.iter().map(|input| input.tag()).collect();
+    let in_commitments: Vec<Commitment> = inputs.iter().map(|input| input.commitment()).collect();
+    let out_commitments: Vec<Commitment> = outputs.iter().map(|output| output.commitment()).collect();
+
+    //balance, with a fee of 5 of asset a
+    assert!(Commitment::is_balanced_with_assets(
+        in_commitments.clone(), out_commitments.clone(), vec!((asset_a, 5))));
+    assert!(!Commitment::is_balanced_with_assets(
+        in_commitments.clone(), out_commitments.clone(), vec!((asset_b, 5))));
+    assert!(!Commitment::is_balanced_with_assets(
+        in_commitments, out_commitments, vec!((asset_a, 4))));
+
+    for (output, input_index) in outputs.iter().zip([0, 1, 0]) {
+        //surjection proof
+        let proof = SurjectionProof::prove(
+            &input_tags, input_index, inputs[input_index].tag_blinding, &output.tag(), output.tag_blinding, b"transaction").unwrap();
+        let proof = SurjectionProof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+        SurjectionProof::verify(&input_tags, &output.tag(), b"transaction", &proof).unwrap();
+        assert!(matches!(SurjectionProof::verify(
+            &input_tags[0..1], &output.tag(), b"transaction", &proof), Err(SignatureError::Malformed)));
+        assert!(matches!(SurjectionProof::verify(
+            &input_tags, &AssetTag::new(&output.asset, Scalar::generate()), b"transaction", &proof), Err(SignatureError::Invalid)));
+        //the proof can't be reused in another transaction
+        assert!(matches!(SurjectionProof::verify(
+            &input_tags, &output.tag(), b"other transaction", &proof), Err(SignatureError::Invalid)));
+
+        //rangeproof
+        let (commitment, proof) = BulletPlusRangeProof::prove_with_asset_tag(
+            output.value, output.blinding, &output.tag()).unwrap();
+        assert!(commitment == output.commitment());
+        BulletPlusRangeProof::verify_with_asset_tag(commitment, &output.tag(), proof.clone()).unwrap();
+        assert!(BulletPlusRangeProof::verify_with_asset_tag(
+            commitment, &AssetTag::unblinded(&output.asset), proof).is_err());
+    }
+
+    //the output's asset is not the chosen input's asset
+    assert!(matches!(SurjectionProof::prove(
+        &input_tags, 1, inputs[1].tag_blinding, &outputs[0].tag(), outputs[0].tag_blinding, b"transaction"),
+        Err(SignatureError::AssetMismatch)));
+}
@@ -0,0 +1,164 @@
+// SPDX short identifier: Unlicense
+
+use ringct::{
+    common::*,
+    address::{
+        Address,
+        ECDHPrivateKey,
+        cryptonote::CryptoNotePrivate,
+        subaddress::MasterPrivateKeys
+    },
+    transaction::{
+        KeyImageStore,
+        MemoryKeyImageStore
+    },
+    proof::{
+        DLEQProof,
+        PaymentProof,
+        ReserveProof,
+        SignedKeyImage,
+        WatchedEnotes
+    }
+};
+
+#[test]
+fn dleq_test() {
+    let x = Scalar::generate();
+    let base_1 = RistrettoPoint::generate();
+    let base_2 = RistrettoPoint::generate();
+    let proof = DLEQProof::prove(&x, &base_1, &base_2, b"message");
+    assert!(DLEQProof::verify(&base_1, &(x * base_1), &base_2, &(x * base_2), b"message", &proof).is_ok());
+
+    //wrong message, or different discrete logs
+    assert!(DLEQProof::verify(&base_1, &(x * base_1), &base_2, &(x * base_2), b"other", &proof).is_err());
+    let y = Scalar::generate();
+    assert!(DLEQProof::verify(&base_1, &(x * base_1), &base_2, &(y * base_2), b"message", &proof).is_err());
+    assert!(DLEQProof::verify(&base_2, &(x * base_2), &base_1, &(x * base_1), b"message", &proof).is_err());
+}
+
+#[test]
+fn payment_proof_test() {
+    let cryptonote_keys = CryptoNotePrivate::generate();
+    let cryptonote = Address::from(cryptonote_keys.to_public());
+    let mut master_keys = MasterPrivateKeys::generate();
+    master_keys.init(2, 1);
+    let subaddress_public = master_keys.get_subaddress((1, 0)).unwrap();
+    let subaddress = Address::from(subaddress_public);
+    let other = Address::from(CryptoNotePrivate::generate().to_public());
+
+    //CryptoNote
+    let transaction_sk = Scalar::generate();
+    let (blinding, recipient) = cryptonote_keys.to_public().send_with_key(100, transaction_sk);
+    let commitment = Commitment::commit(100, blinding);
+    let proof = PaymentProof::prove(&transaction_sk, &cryptonote, &recipient, None).unwrap();
+    let transaction_key = transaction_sk.to_public();
+    assert!(PaymentProof::verify(&cryptonote, &transaction_key, &recipient, &commitment, &proof).unwrap() == 100);
+    //the proof is only valid for the address and amount it was sent with
+    assert!(PaymentProof::verify(&other, &transaction_key, &recipient, &commitment, &proof).is_err());
+    assert!(PaymentProof::verify(&cryptonote, &transaction_key, &recipient, &Commitment::commit(200, blinding), &proof).is_err());
+    assert!(PaymentProof::verify(&cryptonote, &RistrettoPoint::generate(), &recipient, &commitment, &proof).is_err());
+    assert!(PaymentProof::prove(&transaction_sk, &other, &recipient, None).is_err());
+    assert!(PaymentProof::prove(&Scalar::generate(), &cryptonote, &recipient, None).is_err());
+
+    //Subaddress
+    let transaction_sk = Scalar::generate();
+    let (blinding, recipient) = subaddress_public.send_with_key(50, transaction_sk);
+    let commitment = Commitment::commit(50, blinding);
+    let proof = PaymentProof::prove(&transaction_sk, &subaddress, &recipient, None).unwrap();
+    let transaction_key = recipient.transaction_key.unwrap();
+    assert!(transaction_key == transaction_sk * subaddress_public.spend);
+    assert!(PaymentProof::verify(&subaddress, &transaction_key, &recipient, &commitment, &proof).unwrap() == 50);
+    let other_subaddress = Address::from(master_keys.get_subaddress((0, 0)).unwrap());
+    assert!(PaymentProof::verify(&other_subaddress, &transaction_key, &recipient, &commitment, &proof).is_err());
+
+    //shared transaction key, where the index of the output is included
+    let transaction_sk = Scalar::generate();
+    let (blinding, recipient) = cryptonote_keys.to_public().send_with_index(30, transaction_sk, 2);
+    let commitment = Commitment::commit(30, blinding);
+    let proof = PaymentProof::prove(&transaction_sk, &cryptonote, &recipient, Some(2)).unwrap();
+    let transaction_key = transaction_sk.to_public();
+    assert!(PaymentProof::verify(&cryptonote, &transaction_key, &recipient, &commitment, &proof).unwrap() == 30);
+    assert!(PaymentProof::prove(&transaction_sk, &cryptonote, &recipient, Some(1)).is_err());
+    assert!(PaymentProof::prove(&transaction_sk, &cryptonote, &recipient, None).is_err());
+}
+
+#[test]
+fn reserve_proof_test() {
+    let enote_keys: Vec<EnoteKeys> = [100u64, 250, 50].iter()
+        .map(|value| EnoteKeys::new(Scalar::generate(), *value, Scalar::generate()))
+        .collect();
+    let enotes: Vec<Enote> = enote_keys.iter().map(|keys| keys.to_enote()).collect();
+
+    let proof = ReserveProof::prove(&enote_keys, 300, b"challenge").unwrap();
+    assert!(ReserveProof::verify(&enotes, 300, b"challenge", &proof).is_ok());
+    //the key images are the standard key images
+    assert!(proof.get_key_images().iter().zip(&enote_keys).all(|(key_image, keys)| *key_image == keys.get_key_image()));
+
+    //wrong amount, message, or enotes
+    assert!(ReserveProof::verify(&enotes, 301, b"challenge", &proof).is_err());
+    assert!(ReserveProof::verify(&enotes, 300, b"other", &proof).is_err());
+    assert!(ReserveProof::verify(&enotes[0..2], 300, b"challenge", &proof).is_err());
+    let mut other_enotes = enotes.clone();
+    other_enotes[1] = EnoteKeys::new(Scalar::generate(), 250, Scalar::generate()).to_enote();
+    assert!(ReserveProof::verify(&other_enotes, 300, b"challenge", &proof).is_err());
+    //a lower commitment, with the same owner
+    other_enotes[1] = EnoteKeys::new(enote_keys[1].owner, 1, enote_keys[1].blinding).to_enote();
+    assert!(ReserveProof::verify(&other_enotes, 300, b"challenge", &proof).is_err());
+
+    //the total value must be at least the amount, and enotes can't be included twice
+    assert!(ReserveProof::prove(&enote_keys, 401, b"challenge").is_err());
+    assert!(ReserveProof::prove(&[enote_keys[1].clone(), enote_keys[1].clone()], 500, b"challenge").is_err());
+    assert!(ReserveProof::prove(&[], 0, b"challenge").is_err());
+
+    //spent enotes are detected with the set of spent key images
+    let mut spent = MemoryKeyImageStore::new();
+    spent.insert_batch(&[EnoteKeys::new(Scalar::generate(), 0, Scalar::generate()).get_key_image()]).unwrap();
+    assert!(proof.is_unspent(&spent));
+    spent.insert_batch(&[enote_keys[2].get_key_image()]).unwrap();
+    assert!(!proof.is_unspent(&spent));
+}
+
+#[test]
+fn key_image_proof_test() {
+    let keys = CryptoNotePrivate::generate();
+    let view_only = keys.to_view_only();
+    let sent: Vec<_> = [10u64, 20, 30].iter().map(|value| {
+        let (blinding, recipient) = keys.to_public().send(*value);
+        (recipient, Commitment::commit(*value, blinding))
+    }).collect();
+
+    //the view-only wallet detects the enotes, but can't tell if they are spent
+    let mut watched = WatchedEnotes::new();
+    for (recipient, commitment) in &sent {
+        assert!(view_only.receive(recipient, commitment).is_some());
+        watched.add(recipient.to_enote(commitment));
+    }
+    watched.add(sent[0].0.to_enote(&sent[0].1));
+    assert!(watched.get_enotes().len() == 3);
+
+    //the spend wallet exports signed key images
+    let enote_keys: Vec<EnoteKeys> = sent.iter()
+        .map(|(recipient, commitment)| keys.receive(recipient, commitment).unwrap().0)
+        .collect();
+    let signed = SignedKeyImage::export(&enote_keys);
+    assert!(signed.iter().all(|signed| signed.verify().is_ok()));
+
+    //invalid signatures are rejected, and nothing is imported
+    let mut forged = signed[1].clone();
+    forged.key_image = EnoteKeys::new(Scalar::generate(), 0, Scalar::generate()).get_key_image();
+    assert!(forged.verify().is_err());
+    assert!(watched.import_key_images(&[signed[0].clone(), forged]).is_err());
+    assert!(watched.get_enotes().iter().all(|enote| enote.key_image.is_none()));
+
+    //key images of unknown enotes are ignored
+    let other = SignedKeyImage::sign(&EnoteKeys::new(Scalar::generate(), 0, Scalar::generate()));
+    assert!(watched.import_key_images(&[signed.clone(), vec!(other)].concat()).unwrap() == 3);
+
+    //spent enotes are marked
+    let mut spent = MemoryKeyImageStore::new();
+    spent.insert_batch(&[enote_keys[1].get_key_image()]).unwrap();
+    assert!(watched.update_spent(&spent) == vec!(sent[1].0.to_enote(&sent[1].1)));
+    assert!(watched.update_spent(&spent).is_empty());
+    assert!(watched.unspent() == vec!(sent[0].0.to_enote(&sent[0].1), sent[2].0.to_enote(&sent[2].1)));
+    assert!(watched.get_enotes()[1].spent);
+}
@@ -49,6 +49,117 @@ fn bulletproofsplus_test() {
     BulletPlusRangeProof::verify(commitments, proof).unwrap();
 }
 
+#[test]
+fn bulletproofsplus_recovery_test() {
+    let value = 1234567890u64;
+    let blinding = Scalar::generate();
+    let seed_nonce = Scalar::generate();
+
+    //prove
+    let (commitment, proof) = BulletPlusRangeProof::prove_with_seed(
+        value, blinding, seed_nonce).unwrap();
+    assert!(commitment == Commitment::commit(value, blinding));
+
+    //serialize
+    let serialized = proof.to_bytes().unwrap();
+    let proof = BulletPlusRangeProof::from_bytes(&serialized).unwrap();
+
+    //the proof is still a normal rangeproof
+    BulletPlusRangeProof::verify(vec!(commitment), proof.clone()).unwrap();
+
+    //recover
+    let recovered = BulletPlusRangeProof::recover_blinding(
+        commitment, proof.clone(), seed_nonce).unwrap();
+    assert!(recovered == blinding);
+
+    //wrong seed nonce gives a wrong blinding factor
+    let recovered = BulletPlusRangeProof::recover_blinding(
+        commitment, proof.clone(), Scalar::generate()).unwrap();
+    assert!(Commitment::commit(value, recovered) != commitment);
+
+    //wrong commitment
+    assert!(matches!(BulletPlusRangeProof::recover_blinding(
+        Commitment::commit(value, Scalar::generate()), proof, seed_nonce), Err(RangeProofError::Invalid)));
+}
+
+#[test]
+fn bulletproofsplus_minimums_test() {
+    let values = vec!(1000u64, 2000, 3000);
+    let minimums = vec!(1000u64, 0, 2500);
+    let blindings = vec!(Scalar::generate(), Scalar::generate(), Scalar::generate());
+
+    //prove
+    let (commitments, proof) = BulletPlusRangeProof::prove_with_minimums(
+        values.clone(), blindings.clone(), minimums.clone()).unwrap();
+
+    //verify
+    BulletPlusRangeProof::verify_with_minimums(
+        commitments.clone(), minimums.clone(), proof.clone()).unwrap();
+
+    //wrong minimums
+    assert!(matches!(BulletPlusRangeProof::verify_with_minimums(
+        commitments.clone(), vec!(1000, 0, 2000), proof.clone()), Err(RangeProofError::Invalid)));
+    assert!(matches!(BulletPlusRangeProof::verify(
+        commitments.clone(), proof.clone()), Err(RangeProofError::Invalid)));
+
+    //mixed batch
+    let (other_commitments, other_proof) = BulletPlusRangeProof::prove(
+        vec!(5u64), vec!(Scalar::generate())).unwrap();
+    BulletPlusRangeProof::batch_verify_with_minimums(
+        vec!(commitments, other_commitments), vec!(minimums, vec!(0)), vec!(proof, other_proof)).unwrap();
+
+    //value below minimum
+    assert!(matches!(BulletPlusRangeProof::prove_with_minimums(
+        values, blindings, vec!(1000, 2001, 0)), Err(RangeProofError::OutOfRange)));
+}
+
+#[test]
+fn bulletproofsplus_bit_range_test() {
+    let mut batched_commitments: Vec<Vec<Commitment>> = Vec::new();
+    let mut batched_bit_ranges: Vec<usize> = Vec::new();
+    let mut batched_proofs: Vec<BulletPlusRangeProof> = Vec::new();
+    for bit_range in [8, 32, 64, 32] {
+        let max = ((1u128 << bit_range) - 1) as u64;
+        let (commitments, proof) = BulletPlusRangeProof::prove_with_bit_range(
+            vec!(0, max, max / 2), vec!(Scalar::generate(), Scalar::generate(), Scalar::generate()), bit_range).unwrap();
+        BulletPlusRangeProof::verify_with_bit_range(commitments.clone(), bit_range, proof.clone()).unwrap();
+
+        batched_commitments.push(commitments);
+        batched_bit_ranges.push(bit_range);
+        batched_proofs.push(proof);
+
+        //out of range
+        if bit_range < BIT_RANGE {
+            assert!(matches!(BulletPlusRangeProof::prove_with_bit_range(
+                vec!(max + 1), vec!(Scalar::generate()), bit_range), Err(RangeProofError::OutOfRange)));
+        }
+    }
+    //mixed batch
+    BulletPlusRangeProof::batch_verify_with_bit_ranges(
+        batched_commitments.clone(), batched_bit_ranges, batched_proofs.clone()).unwrap();
+
+    //wrong bit range
+    assert!(matches!(BulletPlusRangeProof::verify_with_bit_range(
+        batched_commitments[1].clone(), 64, batched_proofs[1].clone()), Err(RangeProofError::Malformed)));
+    assert!(matches!(BulletPlusRangeProof::verify(
+        batched_commitments[0].clone(), batched_proofs[0].clone()), Err(RangeProofError::Malformed)));
+
+    //the bit range is encoded with the proof
+    let serialized = batched_proofs[0].to_bytes().unwrap();
+    let deserialized = BulletPlusRangeProof::from_bytes(&serialized).unwrap();
+    assert!(deserialized.bit_range() == 8);
+    BulletPlusRangeProof::verify_with_bit_range(batched_commitments[0].clone(), 8, deserialized).unwrap();
+    let mut serialized = serialized;
+    serialized[0] = 24;
+    assert!(BulletPlusRangeProof::from_bytes(&serialized).is_err());
+
+    //unsupported bit range
+    assert!(matches!(BulletPlusRangeProof::prove_with_bit_range(
+        vec!(1), vec!(Scalar::generate()), 24), Err(RangeProofError::Malformed)));
+    assert!(matches!(BulletPlusRangeProof::prove_with_bit_range(
+        vec!(1), vec!(Scalar::generate()), 128), Err(RangeProofError::Malformed)));
+}
+
 #[test]
 fn borromean_test() {
     //prove
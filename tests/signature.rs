@@ -6,8 +6,24 @@ use ringct::{
     common::*,
     signature::{
         MLSAGSignature,
-        CLSAGSignature
-    }
+        CLSAGSignature,
+        LSAGSignature,
+        SAGSignature,
+        balanced_blindings,
+        sort_public_keys,
+        multisig::{
+            MultisigKeyShare,
+            MultisigNonce,
+            MultisigContext,
+            aggregate_public_keys
+        },
+        threshold::{
+            ThresholdShare,
+            ThresholdCryptoNote,
+            combine_shared_secret
+        }
+    },
+    address::cryptonote::CryptoNotePrivate
 };
 
 const RING_SIZES: [usize; 14] = [1, 2, 3, 4, 8, 11, 16, 25, 32, 64, 100, 128, 256, 512];
@@ -127,4 +143,282 @@ fn clsag_test() {
         assert!(CLSAGSignature::verify(
             deserialized, &ring, pseudo_out, b"123456").is_err());
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn clsag_multiple_test() {
+    for n in [1, 2, 5] {
+        let mut inputs: Vec<(Ring, EnoteKeys)> = Vec::new();
+        let mut in_value: u64 = 0;
+        for _ in 0..n {
+            let my_keys = EnoteKeys {
+                owner: Scalar::generate(),
+                value: thread_rng().gen::<u32>() as u64,
+                blinding: Scalar::generate()
+            };
+            in_value += my_keys.value;
+            let mut ring: Ring = Ring::new();
+            for _ in 0..10 {
+                ring.push(Enote::new(RistrettoPoint::generate(), Commitment(RistrettoPoint::generate())));
+            }
+            ring.push(my_keys.to_enote());
+            ring.sort();
+            inputs.push((ring, my_keys));
+        }
+        let rings: Vec<Ring> = inputs.iter().map(|(ring, _)| ring.clone()).collect();
+
+        //2 outputs and a fee
+        let fee = in_value % 1000;
+        let out_values = vec!((in_value - fee) / 2, (in_value - fee) - ((in_value - fee) / 2));
+        let out_blindings = vec!(Scalar::generate(), Scalar::generate());
+        let out_commitments: Vec<Commitment> = out_values.iter().zip(&out_blindings)
+            .map(|(value, blinding)| Commitment::commit(*value, *blinding)).collect();
+
+        //sign
+        let (pseudo_outs, signatures) = CLSAGSignature::sign_multiple(
+            inputs, &out_blindings, b"abcdef").unwrap();
+        assert!(Commitment::is_balanced(pseudo_outs.clone(), out_commitments, fee));
+
+        //verify
+        for ((ring, pseudo_out), signature) in rings.iter().zip(pseudo_outs).zip(signatures) {
+            CLSAGSignature::verify(signature, ring, pseudo_out, b"abcdef").unwrap();
+        }
+    }
+
+    //blindings
+    let out_blindings = vec!(Scalar::generate(), Scalar::generate(), Scalar::generate());
+    let blindings = balanced_blindings(4, &out_blindings);
+    assert!(blindings.len() == 4);
+    assert!(blindings.iter().sum::<Scalar>() == out_blindings.iter().sum::<Scalar>());
+    assert!(balanced_blindings(0, &out_blindings).is_empty());
+}
+
+#[test]
+fn multisig_test() {
+    const PARTIES: usize = 3;
+    let private_keys: Vec<Scalar> = (0..PARTIES).map(|_| Scalar::generate()).collect();
+    let public_keys: Vec<RistrettoPoint> = private_keys.iter().map(|key| key * G).collect();
+
+    //key aggregation
+    let key_shares: Vec<MultisigKeyShare> = private_keys.iter()
+        .map(|key| MultisigKeyShare::new(*key, &public_keys).unwrap()).collect();
+    let public_key = aggregate_public_keys(&public_keys).unwrap();
+    assert!(key_shares.iter().all(|share| share.public_key() == public_key));
+    assert!(MultisigKeyShare::new(Scalar::generate(), &public_keys).is_err());
+
+    //the enote, and a ring containing it
+    let value = 1234567890u64;
+    let blinding = Scalar::generate();
+    let mut ring = Ring::new();
+    for _ in 0..10 {
+        ring.push(Enote::new(RistrettoPoint::generate(), Commitment(RistrettoPoint::generate())));
+    }
+    ring.push(Enote::new(public_key, Commitment::commit(value, blinding)));
+    ring.sort();
+
+    //partial key images
+    let partial_key_images: Vec<RistrettoPoint> = key_shares.iter()
+        .map(|share| share.partial_key_image()).collect();
+
+    //nonce commitments, then reveals
+    let (nonces, public_nonces): (Vec<MultisigNonce>, Vec<_>) = key_shares.iter()
+        .map(MultisigNonce::generate).unzip();
+    let commitments: Vec<[u8; 32]> = public_nonces.iter().map(|nonce| nonce.commitment()).collect();
+    for (nonce, commitment) in public_nonces.iter().zip(&commitments) {
+        nonce.verify_commitment(commitment).unwrap();
+    }
+    assert!(public_nonces[0].verify_commitment(&commitments[1]).is_err());
+
+    //partial signatures
+    let context = MultisigContext::new(
+        &ring, public_key, value, blinding, Scalar::generate(), &partial_key_images, &public_nonces).unwrap();
+    let context = MultisigContext::from_bytes(&context.to_bytes().unwrap()).unwrap();
+    let partial_responses: Vec<Scalar> = key_shares.iter().zip(nonces)
+        .map(|(share, nonce)| share.partial_sign(nonce, &context, b"abcdef").unwrap()).collect();
+
+    //combine, and verify normally
+    let (pseudo_out, signature) = context.combine(&partial_responses, b"abcdef").unwrap();
+    assert!(pseudo_out == context.pseudo_out());
+    assert!(signature.key_image == partial_key_images.iter().sum());
+    CLSAGSignature::verify(signature, &ring, pseudo_out, b"abcdef").unwrap();
+
+    //a missing or wrong partial response
+    assert!(context.combine(&partial_responses[0..PARTIES - 1], b"abcdef").is_err());
+    assert!(context.combine(&partial_responses, b"123456").is_err());
+
+    //a malformed context, with the signer's index outside the ring, or the wrong number of responses
+    let bytes = context.to_bytes().unwrap();
+    let index_offset = ring.to_bytes().unwrap().len();
+    let mut bad_index = bytes.clone();
+    bad_index[index_offset..index_offset + 8].copy_from_slice(&(ring.0.len() as u64).to_le_bytes());
+    let responses_offset = bytes.len() - (ring.0.len() * 32) - 8;
+    let mut bad_responses = bytes[0..bytes.len() - 32].to_vec();
+    bad_responses[responses_offset..responses_offset + 8].copy_from_slice(&(ring.0.len() as u64 - 1).to_le_bytes());
+    for bad_bytes in [bad_index, bad_responses] {
+        let bad_context = MultisigContext::from_bytes(&bad_bytes).unwrap();
+        let (nonce, _) = MultisigNonce::generate(&key_shares[0]);
+        assert!(matches!(key_shares[0].partial_sign(nonce, &bad_context, b"abcdef"), Err(SignatureError::Malformed)));
+        assert!(matches!(bad_context.combine(&partial_responses, b"abcdef"), Err(SignatureError::Malformed)));
+    }
+}
+
+#[test]
+fn threshold_test() {
+    const THRESHOLD: usize = 2;
+    const PARTIES: usize = 3;
+    let keys = CryptoNotePrivate::generate();
+    let parties = ThresholdCryptoNote::deal(&keys, THRESHOLD, PARTIES).unwrap();
+    assert!(ThresholdShare::deal(&keys.spend, PARTIES + 1, PARTIES).is_err());
+    assert!(ThresholdShare::deal(&keys.spend, 0, PARTIES).is_err());
+
+    //send to the shared address
+    let value = 1234567890u64;
+    let (blinding, recipient) = keys.to_public().send(value);
+    let commitment = Commitment::commit(value, blinding);
+    let transaction_key = recipient.transaction_key.unwrap();
+    let (expected, _) = keys.receive(&recipient, &commitment).unwrap();
+
+    //any 2 of the 3 parties, here the first and last
+    let signers: Vec<u64> = vec!(parties[0].spend.index(), parties[2].spend.index());
+    let signing_parties = vec!(&parties[0], &parties[2]);
+
+    //receive, by combining partial shared secrets
+    let partial_shared_secrets: Vec<RistrettoPoint> = signing_parties.iter()
+        .map(|party| party.partial_shared_secret(&signers, &transaction_key).unwrap()).collect();
+    let enotes: Vec<_> = signing_parties.iter()
+        .map(|party| party.receive(&recipient, &commitment, combine_shared_secret(&partial_shared_secrets)).unwrap())
+        .collect();
+    assert!(enotes.iter().all(|enote| enote.value == value && enote.blinding == blinding));
+    assert!(parties[0].receive(&recipient, &commitment, combine_shared_secret(&partial_shared_secrets[0..1])).is_none());
+
+    //not enough, or duplicate signers
+    assert!(parties[0].spend.to_key_share(&[1], recipient.public_key).is_err());
+    assert!(parties[0].spend.to_key_share(&[1, 1], recipient.public_key).is_err());
+    assert!(parties[0].spend.to_key_share(&[2, 3], recipient.public_key).is_err());
+
+    //participants are numbered from 1
+    assert!(matches!(parties[0].spend.to_key_share(&[0, 1], recipient.public_key), Err(SignatureError::Malformed)));
+    let mut bytes = parties[0].spend.to_bytes().unwrap();
+    bytes[0..8].copy_from_slice(&0u64.to_le_bytes());
+    let share = ThresholdShare::from_bytes(&bytes).unwrap();
+    assert!(share.index() == 0);
+    assert!(matches!(share.to_key_share(&[0, 2], recipient.public_key), Err(SignatureError::Malformed)));
+    assert!(matches!(share.partial_shared_secret(&[0, 2], &transaction_key), Err(SignatureError::Malformed)));
+
+    //the enote, and a ring containing it
+    let mut ring = Ring::new();
+    for _ in 0..10 {
+        ring.push(Enote::new(RistrettoPoint::generate(), Commitment(RistrettoPoint::generate())));
+    }
+    ring.push(Enote::new(recipient.public_key, commitment));
+    ring.sort();
+
+    //sign with the multisig protocol
+    let key_shares: Vec<MultisigKeyShare> = enotes.iter()
+        .map(|enote| enote.owner.to_key_share(&signers, enote.public_key).unwrap()).collect();
+    let partial_key_images: Vec<RistrettoPoint> = key_shares.iter()
+        .map(|share| share.partial_key_image()).collect();
+    let (nonces, public_nonces): (Vec<MultisigNonce>, Vec<_>) = key_shares.iter()
+        .map(MultisigNonce::generate).unzip();
+    let context = MultisigContext::new(
+        &ring, recipient.public_key, value, blinding, Scalar::generate(), &partial_key_images, &public_nonces).unwrap();
+    let partial_responses: Vec<Scalar> = key_shares.iter().zip(nonces)
+        .map(|(share, nonce)| share.partial_sign(nonce, &context, b"abcdef").unwrap()).collect();
+
+    //the key image is the same as the single-signer key image
+    let (pseudo_out, signature) = context.combine(&partial_responses, b"abcdef").unwrap();
+    assert!(signature.key_image == expected.get_key_image());
+    CLSAGSignature::verify(signature, &ring, pseudo_out, b"abcdef").unwrap();
+
+    //a single signer can't sign
+    let key_share = enotes[0].owner.to_key_share(&signers, recipient.public_key).unwrap();
+    let (nonce, public_nonce) = MultisigNonce::generate(&key_share);
+    let context = MultisigContext::new(
+        &ring, recipient.public_key, value, blinding, Scalar::generate(),
+        &[key_share.partial_key_image()], &[public_nonce]).unwrap();
+    let partial_response = key_share.partial_sign(nonce, &context, b"abcdef").unwrap();
+    assert!(context.combine(&[partial_response], b"abcdef").is_err());
+}
+
+#[test]
+fn lsag_test() {
+    for x in RING_SIZES {
+        let keys: Vec<Scalar> = (0..x).map(|_| Scalar::generate()).collect();
+        let ring = sort_public_keys(&keys.iter().map(|key| key * G).collect::<Vec<_>>());
+        let my_key = &keys[thread_rng().gen::<usize>() % x];
+
+        if x != 1 {
+            //should fail, because the ring is unsorted
+            let mut reversed = ring.clone();
+            reversed.reverse();
+            assert!(LSAGSignature::sign(&reversed, my_key, b"poll 1", b"yes").is_err());
+        }
+
+        //sign
+        let sig = LSAGSignature::sign(&ring, my_key, b"poll 1", b"yes").unwrap();
+
+        //serialize
+        let serialized = sig.to_bytes().unwrap();
+        let deserialized = LSAGSignature::from_bytes(&serialized).unwrap();
+
+        //verify
+        LSAGSignature::verify(&deserialized, &ring, b"poll 1", b"yes").unwrap();
+
+        //wrong message or context
+        assert!(LSAGSignature::verify(&deserialized, &ring, b"poll 1", b"no").is_err());
+        assert!(LSAGSignature::verify(&deserialized, &ring, b"poll 2", b"yes").is_err());
+
+        //the key image is linked within a context, but not across contexts or to the standard key image
+        let same_context = LSAGSignature::sign(&ring, my_key, b"poll 1", b"no").unwrap();
+        let other_context = LSAGSignature::sign(&ring, my_key, b"poll 2", b"yes").unwrap();
+        assert!(same_context.get_key_image() == sig.get_key_image());
+        assert!(other_context.get_key_image() != sig.get_key_image());
+        assert!(sig.get_key_image() != EnoteKeys::new(*my_key, 0, Scalar::generate()).get_key_image());
+
+        //a key outside the ring can't sign
+        assert!(LSAGSignature::sign(&ring, &Scalar::generate(), b"poll 1", b"yes").is_err());
+    }
+}
+
+#[test]
+fn sag_test() {
+    for x in RING_SIZES {
+        let keys: Vec<Scalar> = (0..x).map(|_| Scalar::generate()).collect();
+        let ring = sort_public_keys(&keys.iter().map(|key| key * G).collect::<Vec<_>>());
+        let my_key = &keys[thread_rng().gen::<usize>() % x];
+
+        if x != 1 {
+            //should fail, because the ring is unsorted
+            let mut reversed = ring.clone();
+            reversed.reverse();
+            assert!(SAGSignature::sign(&reversed, my_key, b"abcdef").is_err());
+        }
+
+        //sign
+        let sig = SAGSignature::sign(&ring, my_key, b"abcdef").unwrap();
+
+        //serialize
+        let serialized = sig.to_bytes().unwrap();
+        let deserialized = SAGSignature::from_bytes(&serialized).unwrap();
+
+        //verify
+        SAGSignature::verify(&deserialized, &ring, b"abcdef").unwrap();
+
+        //wrong message
+        assert!(SAGSignature::verify(&deserialized, &ring, b"123456").is_err());
+
+        //a key outside the ring can't sign
+        assert!(SAGSignature::sign(&ring, &Scalar::generate(), b"abcdef").is_err());
+    }
+
+    //CryptoNote addresses, in any order
+    let keys: Vec<CryptoNotePrivate> = (0..8).map(|_| CryptoNotePrivate::generate()).collect();
+    let addresses: Vec<_> = keys.iter().map(|keys| keys.to_public()).collect();
+    let sig = SAGSignature::sign_with_addresses(&addresses, &keys[3], b"abcdef").unwrap();
+    SAGSignature::verify_with_addresses(&sig, &addresses, b"abcdef").unwrap();
+    let mut reversed = addresses.clone();
+    reversed.reverse();
+    SAGSignature::verify_with_addresses(&sig, &reversed, b"abcdef").unwrap();
+    assert!(SAGSignature::verify_with_addresses(&sig, &addresses[1..], b"abcdef").is_err());
+    assert!(SAGSignature::sign_with_addresses(&addresses[4..], &keys[3], b"abcdef").is_err());
+}
@@ -0,0 +1,308 @@
+// SPDX short identifier: Unlicense
+
+use ringct::{
+    common::*,
+    rangeproof::BulletPlusRangeProof,
+    address::{
+        Address,
+        cryptonote::CryptoNotePrivate,
+        subaddress::MasterPrivateKeys
+    },
+    transaction::{
+        Transaction,
+        TransactionBuilder,
+        KeyImageStore,
+        MemoryKeyImageStore,
+        FileKeyImageStore
+    }
+};
+
+const RINGSIZE: usize = 11;
+
+fn random_ring(enote: Enote) -> Ring {
+    let mut ring = Ring::new();
+    for _ in 0..RINGSIZE - 1 {
+        ring.push(Enote::new(RistrettoPoint::generate(), Commitment(RistrettoPoint::generate())));
+    }
+    ring.push(enote);
+    return ring
+}
+
+fn random_enote_keys(value: u64) -> EnoteKeys {
+    return EnoteKeys::new(Scalar::generate(), value, Scalar::generate())
+}
+
+#[test]
+fn transaction_builder_test() {
+    let receiver_keys = CryptoNotePrivate::generate();
+    let mut change_keys = MasterPrivateKeys::generate();
+    change_keys.init(1, 1);
+    let change_address = change_keys.get_subaddress((0, 0)).unwrap();
+
+    let input_1 = random_enote_keys(700);
+    let input_2 = random_enote_keys(300);
+
+    let mut builder = TransactionBuilder::new();
+    builder
+        .add_input(random_ring(input_1.to_enote()), input_1.clone())
+        .add_input(random_ring(input_2.to_enote()), input_2.clone())
+        .add_output(Address::from(receiver_keys.to_public()), 600)
+        .add_output(Address::from(change_address), 350)
+        .set_fee(50);
+    let transaction = builder.build().unwrap();
+
+    //serialize
+    let serialized = transaction.to_bytes().unwrap();
+    let transaction = Transaction::from_bytes(&serialized).unwrap();
+
+    //verify
+    transaction.verify().unwrap();
+    let prefix_hash = transaction.prefix_hash();
+    assert!(transaction.key_images() == vec!(input_1.get_key_image(), input_2.get_key_image()));
+
+    //the prefix hash commits to the fee and outputs
+    let mut modified = transaction.clone();
+    modified.fee += 1;
+    assert!(modified.prefix_hash() != prefix_hash);
+    let mut modified = transaction.clone();
+    modified.outputs.swap(0, 1);
+    assert!(modified.prefix_hash() != prefix_hash);
+
+    //receive
+    let output = &transaction.outputs[0];
+    assert!(receiver_keys.receive(&output.recipient, &output.commitment).unwrap().0.value == 600);
+    let output = &transaction.outputs[1];
+    assert!(change_keys.receive(&output.recipient, &output.commitment).unwrap().value == 350);
+
+    //unbalanced
+    let mut builder = TransactionBuilder::new();
+    builder
+        .add_input(random_ring(input_1.to_enote()), input_1.clone())
+        .add_output(Address::from(receiver_keys.to_public()), 700)
+        .set_fee(1);
+    assert!(matches!(builder.build(), Err(TransactionError::Unbalanced)));
+
+    //input not in ring
+    let mut builder = TransactionBuilder::new();
+    builder
+        .add_input(random_ring(input_2.to_enote()), input_1.clone())
+        .add_output(Address::from(receiver_keys.to_public()), 700);
+    assert!(matches!(builder.build(), Err(TransactionError::Input(0, SignatureError::EnoteNotInRing))));
+
+    //empty
+    assert!(matches!(TransactionBuilder::new().build(), Err(TransactionError::Empty)));
+}
+
+#[test]
+fn shared_transaction_key_test() {
+    let receiver_keys = CryptoNotePrivate::generate();
+    let mut change_keys = MasterPrivateKeys::generate();
+    change_keys.init(1, 1);
+    let change_address = change_keys.get_subaddress((0, 0)).unwrap();
+    let input = random_enote_keys(1000);
+
+    let mut builder = TransactionBuilder::new();
+    builder
+        .add_input(random_ring(input.to_enote()), input.clone())
+        .add_output(Address::from(receiver_keys.to_public()), 400)
+        .add_output(Address::from(receiver_keys.to_public()), 400)
+        .add_output(Address::from(change_address), 150)
+        .set_fee(50);
+    let unshared = builder.build().unwrap();
+    let transaction = builder.share_transaction_key().build().unwrap();
+    transaction.verify().unwrap();
+    assert!(transaction.to_bytes().unwrap().len() < unshared.to_bytes().unwrap().len());
+
+    //the shared key is committed to
+    let transaction_key = transaction.transaction_key.unwrap();
+    let mut modified = transaction.clone();
+    modified.transaction_key = Some(RistrettoPoint::generate());
+    assert!(modified.prefix_hash() != transaction.prefix_hash());
+
+    //receive, with the index of each output
+    let outputs = &transaction.outputs;
+    let (output_1, _) = receiver_keys.receive_with_index(
+        &outputs[0].recipient, &outputs[0].commitment, transaction_key, 0).unwrap();
+    let (output_2, _) = receiver_keys.receive_with_index(
+        &outputs[1].recipient, &outputs[1].commitment, transaction_key, 1).unwrap();
+    assert!(output_1.value == 400 && output_2.value == 400);
+    assert!(output_1.owner != output_2.owner);
+    assert!(receiver_keys.receive_with_index(
+        &outputs[0].recipient, &outputs[0].commitment, transaction_key, 1).is_none());
+    assert!(receiver_keys.receive(&outputs[0].recipient, &outputs[0].commitment).is_none());
+
+    //subaddress outputs have their own transaction key
+    assert!(outputs[2].recipient.transaction_key.is_some());
+    assert!(change_keys.receive(&outputs[2].recipient, &outputs[2].commitment).unwrap().value == 150);
+
+    //only subaddress outputs
+    let mut builder = TransactionBuilder::new();
+    builder
+        .add_input(random_ring(input.to_enote()), input.clone())
+        .add_output(Address::from(change_address), 1000)
+        .share_transaction_key();
+    assert!(builder.build().unwrap().transaction_key.is_none());
+}
+
+#[test]
+fn outgoing_view_key_test() {
+    let sender_keys = CryptoNotePrivate::generate();
+    let receiver_keys = CryptoNotePrivate::generate();
+    let mut change_keys = MasterPrivateKeys::generate();
+    change_keys.init(1, 1);
+    let change_address = change_keys.get_subaddress((0, 0)).unwrap();
+    let input = random_enote_keys(1000);
+
+    let outgoing = sender_keys.outgoing_view_key();
+    let mut builder = TransactionBuilder::new();
+    builder
+        .add_input(random_ring(input.to_enote()), input.clone())
+        .add_output(Address::from(receiver_keys.to_public()), 600)
+        .add_output(Address::from(change_address), 350)
+        .set_fee(50)
+        .share_transaction_key()
+        .set_outgoing_view_key(&outgoing);
+    let transaction = builder.build().unwrap();
+    transaction.verify().unwrap();
+
+    //each output has its own transaction key, and is received normally
+    assert!(transaction.transaction_key.is_none());
+    let outputs = &transaction.outputs;
+    assert!(receiver_keys.receive(&outputs[0].recipient, &outputs[0].commitment).unwrap().0.value == 600);
+    assert!(change_keys.receive(&outputs[1].recipient, &outputs[1].commitment).unwrap().value == 350);
+
+    //the sender can recover both outputs
+    let sent = outgoing.recover(&outputs[0].recipient, &outputs[0].commitment).unwrap();
+    assert!(sent.destination == Address::from(receiver_keys.to_public()) && sent.value == 600);
+    let sent = outgoing.recover(&outputs[1].recipient, &outputs[1].commitment).unwrap();
+    assert!(sent.destination == Address::from(change_address) && sent.value == 350);
+    assert!(CryptoNotePrivate::generate().outgoing_view_key()
+        .recover(&outputs[0].recipient, &outputs[0].commitment).is_none());
+}
+
+#[test]
+fn transaction_verify_test() {
+    let receiver = Address::from(CryptoNotePrivate::generate().to_public());
+    let input_1 = random_enote_keys(500);
+    let input_2 = random_enote_keys(500);
+
+    let mut builder = TransactionBuilder::new();
+    builder
+        .add_input(random_ring(input_1.to_enote()), input_1.clone())
+        .add_input(random_ring(input_2.to_enote()), input_2.clone())
+        .add_output(receiver, 400)
+        .add_output(receiver, 500)
+        .set_fee(100);
+    let transaction = builder.build().unwrap();
+    transaction.verify().unwrap();
+
+    //duplicate key image
+    let mut modified = transaction.clone();
+    modified.inputs.push(modified.inputs[0].clone());
+    assert!(matches!(modified.verify(), Err(TransactionError::DuplicateKeyImage(2))));
+
+    //duplicate output key
+    let mut modified = transaction.clone();
+    modified.outputs[1].recipient.public_key = modified.outputs[0].recipient.public_key;
+    assert!(matches!(modified.verify(), Err(TransactionError::DuplicateOutputKey(1))));
+
+    //unbalanced
+    let mut modified = transaction.clone();
+    modified.fee -= 1;
+    assert!(matches!(modified.verify(), Err(TransactionError::Unbalanced)));
+
+    //invalid rangeproof
+    let mut modified = transaction.clone();
+    modified.rangeproof = BulletPlusRangeProof::prove(
+        vec!(400, 500), vec!(Scalar::generate(), Scalar::generate())).unwrap().1;
+    assert!(matches!(modified.verify(), Err(TransactionError::RangeProof(RangeProofError::Invalid))));
+
+    //modified prefix invalidates the signatures
+    let mut modified = transaction.clone();
+    modified.outputs[1].recipient.encrypted_amount ^= 1;
+    assert!(matches!(modified.verify(), Err(TransactionError::Input(0, SignatureError::Invalid))));
+
+    //signature from another transaction
+    let mut modified = transaction.clone();
+    let mut builder = TransactionBuilder::new();
+    builder
+        .add_input(modified.inputs[1].ring.clone(), input_2.clone())
+        .add_output(receiver, 500);
+    modified.inputs[1].signature = builder.build().unwrap().inputs[0].signature.clone();
+    assert!(matches!(modified.verify(), Err(TransactionError::Input(1, SignatureError::Invalid))));
+}
+
+#[test]
+fn key_image_store_test() {
+    let receiver = Address::from(CryptoNotePrivate::generate().to_public());
+    let input = random_enote_keys(100);
+    let mut builder = TransactionBuilder::new();
+    builder
+        .add_input(random_ring(input.to_enote()), input.clone())
+        .add_output(receiver, 100);
+    let transaction = builder.build().unwrap();
+
+    let path = std::env::temp_dir().join(format!("ringct_key_images_{}", rand::random::<u64>()));
+    let mut stores: Vec<Box<dyn KeyImageStore>> = vec!(
+        Box::new(MemoryKeyImageStore::new()),
+        Box::new(FileKeyImageStore::open(&path).unwrap())
+    );
+
+    for store in stores.iter_mut() {
+        let key_images: Vec<RistrettoPoint> = (0..4).map(|_| RistrettoPoint::generate()).collect();
+
+        //not yet spent
+        transaction.verify_with_store(store.as_ref()).unwrap();
+        store.insert_batch(&transaction.key_images()).unwrap();
+        assert!(store.contains(&input.get_key_image()));
+        assert!(matches!(
+            transaction.verify_with_store(store.as_ref()), Err(TransactionError::KeyImageSpent(0))));
+
+        //batches are atomic
+        assert!(matches!(
+            store.insert_batch(&[key_images[0], transaction.key_images()[0]]),
+            Err(KeyImageStoreError::AlreadySpent(1))));
+        assert!(!store.contains(&key_images[0]));
+        assert!(matches!(
+            store.insert_batch(&[key_images[0], key_images[0]]),
+            Err(KeyImageStoreError::AlreadySpent(1))));
+        store.insert_batch(&key_images).unwrap();
+        assert!(matches!(
+            store.remove(&[key_images[0], RistrettoPoint::generate()]),
+            Err(KeyImageStoreError::NotFound(1))));
+        assert!(store.contains(&key_images[0]));
+
+        //rollback
+        store.remove(&transaction.key_images()).unwrap();
+        transaction.verify_with_store(store.as_ref()).unwrap();
+        store.remove(&key_images[0..2]).unwrap();
+    }
+
+    //reopen the file-backed store
+    drop(stores);
+    let mut store = FileKeyImageStore::open(&path).unwrap();
+    assert!(store.len() == 2);
+    assert!(!store.contains(&input.get_key_image()));
+
+    //a batch interrupted while being written is discarded
+    let length = std::fs::metadata(&path).unwrap().len();
+    store.insert_batch(&transaction.key_images()).unwrap();
+    drop(store);
+    let bytes = std::fs::read(&path).unwrap();
+    for torn_length in [length + 3, length + 40, bytes.len() as u64 - 1] {
+        std::fs::write(&path, &bytes[0..torn_length as usize]).unwrap();
+        let mut store = FileKeyImageStore::open(&path).unwrap();
+        assert!(store.len() == 2 && !store.contains(&input.get_key_image()));
+        assert!(std::fs::metadata(&path).unwrap().len() == length);
+        store.insert_batch(&transaction.key_images()).unwrap();
+        drop(store);
+        assert!(FileKeyImageStore::open(&path).unwrap().contains(&input.get_key_image()));
+    }
+
+    //a modified batch before the last one is corrupted
+    let mut modified = bytes.clone();
+    modified[10] ^= 1;
+    std::fs::write(&path, &modified).unwrap();
+    assert!(matches!(FileKeyImageStore::open(&path), Err(KeyImageStoreError::Corrupted)));
+    std::fs::remove_file(&path).unwrap();
+}